@@ -1,17 +1,18 @@
 use steam_tradeoffers::{
     TradeOfferManager,
+    PollEvent,
     response::{TradeOffer, Asset},
-    enums::TradeOfferState,
     error::Error,
     SteamID,
     chrono::Duration,
 };
+use futures::StreamExt;
 use dotenv::dotenv;
 use std::env;
 
-fn assets_item_names<'a>(
-    assets: &'a Vec<Asset>,
-) -> Vec<&'a str> {
+fn assets_item_names(
+    assets: &[Asset],
+) -> Vec<&str> {
     assets
         .iter()
         .map(|item| item.classinfo.market_hash_name.as_ref())
@@ -66,36 +67,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let manager = TradeOfferManager::builder(steamid, key)
         .identity_secret(String::from("secret"))
         .cancel_duration(Duration::minutes(30))
+        .poll_data_path(format!("assets/poll_data_{}.json", u64::from(steamid)))
         .build();
     let (sessionid, cookies) = get_session();
     
     manager.set_session(&sessionid, &cookies)?;
     
+    let mut events = manager.start_polling(std::time::Duration::from_secs(30));
+    
     // gets changes to trade offers for account
-    for (mut offer, old_state) in manager.do_poll(true).await? {
-        if let Some(state) = old_state {
-            println!(
-                "Offer {} changed state: {} -> {}",
-                offer,
-                state,
-                offer.trade_offer_state
-            );
-        } else if
-            offer.trade_offer_state == TradeOfferState::Active &&
-            !offer.is_our_offer
-        {
-            println!("New offer {}", offer);
-            println!("Receiving: {:?}", assets_item_names(&offer.items_to_receive));
-            println!("Giving: {:?}", assets_item_names(&offer.items_to_give));
-            
-            // free items
-            if offer.items_to_give.is_empty() {
-                if let Err(error) = accept_offer(&manager, &mut offer).await {
-                    println!("Error accepting offer {}: {}", offer, error);
-                } else {
-                    println!("Accepted offer {}", offer);
+    while let Some(event) = events.next().await {
+        match event {
+            PollEvent::NewReceivedOffer(mut offer) => {
+                println!("New offer {}", offer);
+                println!("Receiving: {:?}", assets_item_names(&offer.items_to_receive));
+                println!("Giving: {:?}", assets_item_names(&offer.items_to_give));
+                
+                // free items
+                if offer.items_to_give.is_empty() {
+                    if let Err(error) = accept_offer(&manager, &mut offer).await {
+                        println!("Error accepting offer {}: {}", offer, error);
+                    } else {
+                        println!("Accepted offer {}", offer);
+                    }
                 }
-            }
+            },
+            PollEvent::ReceivedOfferChanged(change) | PollEvent::SentOfferChanged(change) => {
+                println!(
+                    "Offer {} changed state: {} -> {}",
+                    change.offer,
+                    change.old_state,
+                    change.new_state
+                );
+            },
+            PollEvent::PollError(error) => {
+                println!("Error polling offers: {}", error);
+            },
+            _ => {},
         }
     }
     
@@ -0,0 +1,239 @@
+use serde::Serialize;
+use crate::{
+    SteamID,
+    request::{serializers::steamid_as_string, trade_offer::Item},
+    response::Currency,
+    serializers::{string, option_string},
+    types::{TradeOfferId, TradeId, AppId, ContextId},
+};
+
+/// Parameters for IEconService/GetTradeOffers.
+#[derive(Serialize, Debug, Clone)]
+pub struct GetTradeOffersRequest<'a> {
+    /// The language for descriptions.
+    pub language: &'a str,
+    /// Whether to only include active offers.
+    pub active_only: bool,
+    /// Whether to only include offers that are no longer active.
+    pub historical_only: bool,
+    /// Whether to include offers we sent.
+    pub get_sent_offers: bool,
+    /// Whether to include offers we received.
+    pub get_received_offers: bool,
+    /// Whether to include descriptions for the items in the offers.
+    pub get_descriptions: bool,
+    /// Offers that are no longer active are only included if they were updated after this
+    /// timestamp.
+    pub time_historical_cutoff: u64,
+    /// The cursor for the next page of offers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<u32>,
+}
+
+/// Parameters for IEconService/GetTradeOffer.
+#[derive(Serialize, Debug, Clone)]
+pub struct GetTradeOfferRequest<'a> {
+    /// The ID of the offer.
+    #[serde(with = "string")]
+    pub tradeofferid: TradeOfferId,
+    /// The language for descriptions.
+    pub language: &'a str,
+    /// Whether to include descriptions for the items in the offer.
+    pub get_descriptions: bool,
+}
+
+/// Parameters for IEconService/GetTradeStatus.
+#[derive(Serialize, Debug, Clone)]
+pub struct GetTradeStatusRequest<'a> {
+    /// The ID of the trade.
+    #[serde(with = "string")]
+    pub tradeid: TradeId,
+    /// The language for descriptions.
+    pub language: &'a str,
+    /// Whether to include descriptions for the items in the trade.
+    pub get_descriptions: bool,
+}
+
+/// Parameters for IEconService/GetTradeHistory.
+#[derive(Serialize, Debug, Clone)]
+pub struct GetTradeHistoryRequest<'a> {
+    /// The maximum number of trades to get.
+    pub max_trades: u32,
+    /// The time of the last trade from the previous page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_after_time: Option<i64>,
+    /// The ID of the last trade from the previous page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "option_string")]
+    pub start_after_tradeid: Option<TradeId>,
+    /// Whether to page towards newer trades rather than older ones.
+    pub navigating_back: bool,
+    /// Whether to include descriptions for the items in the trades.
+    pub get_descriptions: bool,
+    /// The language for descriptions.
+    pub language: &'a str,
+    /// Whether to include failed trades.
+    pub include_failed: bool,
+    /// Whether to include the total number of trades.
+    pub include_total: bool,
+}
+
+/// Parameters for IEconService/GetTradeOffersSummary.
+#[derive(Serialize, Debug, Clone)]
+pub struct GetTradeOffersSummaryRequest {
+    /// Offers are counted as new or updated if they changed after this timestamp.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_last_visit: Option<i64>,
+}
+
+/// Parameters for IEconService/GetTradeHoldDurations.
+#[derive(Serialize, Debug, Clone)]
+pub struct GetTradeHoldDurationsRequest<'a> {
+    /// The partner's SteamID.
+    #[serde(serialize_with = "steamid_as_string")]
+    pub steamid_target: &'a SteamID,
+    /// The partner's trade offer access token. Required if we are not friends with the partner.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trade_offer_access_token: &'a Option<String>,
+}
+
+/// The query for a trade offer page e.g. `/tradeoffer/new/?partner=...&token=...`.
+#[derive(Serialize, Debug, Clone)]
+pub struct TradeOfferPageQuery<'a> {
+    /// The partner's account ID.
+    pub partner: u32,
+    /// The partner's trade offer access token.
+    pub token: &'a Option<String>,
+}
+
+/// One side of an offer in [`OfferForm`].
+#[derive(Serialize, Debug, Clone)]
+pub struct OfferFormUser<'a> {
+    /// The items this side gives.
+    pub assets: &'a Vec<Item>,
+    /// The currencies this side gives.
+    pub currency: &'a Vec<Currency>,
+    /// Whether this side has readied up. Always `false` for offers.
+    pub ready: bool,
+}
+
+/// The offer as sent in the `json_tradeoffer` field of [`SendOfferRequest`].
+#[derive(Serialize, Debug, Clone)]
+pub struct OfferForm<'a> {
+    /// Whether this is a new version of the offer. Always `true`.
+    pub newversion: bool,
+    /// The version of the offer. One more than the number of items in the offer.
+    pub version: u32,
+    /// What we give.
+    pub me: OfferFormUser<'a>,
+    /// What the partner gives.
+    pub them: OfferFormUser<'a>,
+}
+
+/// Sent in the `trade_offer_create_params` field of [`SendOfferRequest`].
+#[derive(Serialize, Debug, Clone)]
+pub struct TradeOfferCreateParams<'a> {
+    /// The partner's trade offer access token. Required if we are not friends with the partner.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trade_offer_access_token: &'a Option<String>,
+}
+
+/// Form for `/tradeoffer/new/send`.
+#[derive(Serialize, Debug, Clone)]
+pub struct SendOfferRequest<'a> {
+    /// The session ID.
+    pub sessionid: &'a str,
+    /// Always `1`.
+    pub serverid: u32,
+    /// The JSON-encoded [`OfferForm`].
+    pub json_tradeoffer: String,
+    /// The message to send with the offer.
+    pub tradeoffermessage: &'a Option<String>,
+    /// Always empty.
+    pub captcha: &'static str,
+    /// The JSON-encoded [`TradeOfferCreateParams`].
+    pub trade_offer_create_params: String,
+    /// The ID of the offer this offer counters.
+    pub tradeofferid_countered: &'a Option<u64>,
+    /// The partner's SteamID.
+    #[serde(serialize_with = "steamid_as_string")]
+    pub partner: &'a SteamID,
+}
+
+/// Form for `/tradeoffer/{tradeofferid}/accept`.
+#[derive(Serialize, Debug, Clone)]
+pub struct AcceptOfferRequest<'a> {
+    /// The session ID.
+    pub sessionid: &'a str,
+    /// Always `1`.
+    pub serverid: u32,
+    /// The ID of the offer.
+    #[serde(with = "string")]
+    pub tradeofferid: TradeOfferId,
+    /// Always empty.
+    pub captcha: &'static str,
+    /// The partner's SteamID.
+    #[serde(serialize_with = "steamid_as_string")]
+    pub partner: &'a SteamID,
+}
+
+/// Form for `/tradeoffer/{tradeofferid}/decline`.
+#[derive(Serialize, Debug, Clone)]
+pub struct DeclineOfferRequest<'a> {
+    /// The session ID.
+    pub sessionid: &'a str,
+}
+
+/// Form for `/tradeoffer/{tradeofferid}/cancel`.
+#[derive(Serialize, Debug, Clone)]
+pub struct CancelOfferRequest<'a> {
+    /// The session ID.
+    pub sessionid: &'a str,
+}
+
+/// Form for `/my/tradeoffers/newtradeurl`.
+#[derive(Serialize, Debug, Clone)]
+pub struct NewTradeUrlRequest<'a> {
+    /// The session ID.
+    pub sessionid: &'a str,
+}
+
+/// Query for `/inventory/{steamid}/{appid}/{contextid}`.
+#[derive(Serialize, Debug, Clone)]
+pub struct GetInventoryRequest<'a> {
+    /// The language for descriptions.
+    pub l: &'a str,
+    /// The maximum number of items to get.
+    pub count: u32,
+    /// The last asset ID from the previous page.
+    pub start_assetid: Option<u64>,
+}
+
+/// Query for `/profiles/{steamid}/inventory/json/{appid}/{contextid}`.
+#[derive(Serialize, Debug, Clone)]
+pub struct GetInventoryOldRequest<'a> {
+    /// The language for descriptions.
+    pub l: &'a str,
+    /// Where the next page starts.
+    pub start: Option<u64>,
+    /// Whether to only include tradable items.
+    pub trading: bool,
+}
+
+/// Query for `/tradeoffer/new/partnerinventory/`.
+#[derive(Serialize, Debug, Clone)]
+pub struct GetPartnerInventoryRequest<'a> {
+    /// The session ID.
+    pub sessionid: &'a str,
+    /// The partner's SteamID.
+    #[serde(serialize_with = "steamid_as_string")]
+    pub partner: &'a SteamID,
+    /// The app of the inventory.
+    pub appid: AppId,
+    /// The context of the inventory.
+    pub contextid: ContextId,
+    /// The language for descriptions.
+    pub l: &'a str,
+    /// Where the next page starts.
+    pub start: Option<u64>,
+}
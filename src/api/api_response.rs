@@ -4,7 +4,8 @@ use std::{
     fmt,
 };
 use crate::{
-    types::ClassInfoAppClass,
+    types::{ClassInfoAppClass, ClassInfoClass, TradeOfferId},
+    serializers::string,
     response::{
         self,
         deserializers::{
@@ -13,6 +14,7 @@ use crate::{
             option_str_to_number,
             deserialize_classinfo_map_raw,
             deserialize_classinfo_map,
+            deserialize_classinfo_seq_raw,
         }
     }
 };
@@ -27,7 +29,7 @@ use serde::{
     },
 };
 
-type RgInventory = HashMap<String, raw::RawAssetOld>;
+pub type RgInventory = HashMap<String, raw::RawAssetOld>;
 
 fn deserialize_rg_inventory<'de, D>(deserializer: D) -> Result<RgInventory, D::Error>
 where
@@ -68,20 +70,78 @@ where
     deserializer.deserialize_any(RgInventoryVisitor)
 }
 
+/// The body of a Steam Web API response. Most methods wrap their result in a `response` key.
 #[derive(Deserialize, Debug)]
-pub struct GetTradeOffersResponseBody {
+pub struct WebAPIResponse<T> {
+    pub response: T,
+}
+
+/// The response from IEconService/GetTradeOffers.
+#[derive(Deserialize, Debug)]
+pub struct GetTradeOffersResponse {
     #[serde(default)]
     pub trade_offers_sent: Vec<raw::RawTradeOffer>,
     #[serde(default)]
     pub trade_offers_received: Vec<raw::RawTradeOffer>,
+    /// Raw classinfos for the items in the offers, if descriptions were requested.
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_classinfo_seq_raw")]
+    pub descriptions: HashMap<ClassInfoClass, String>,
+    /// The cursor for the next page of offers. `0` if there are no more pages.
     pub next_cursor: Option<u32>,
 }
 
+/// The response from IEconService/GetTradeOffer.
 #[derive(Deserialize, Debug)]
-pub struct GetTradeOffersResponse {
-    pub response: GetTradeOffersResponseBody,
+pub struct GetTradeOfferResponse {
+    pub offer: raw::RawTradeOffer,
+    /// Raw classinfos for the items in the offer, if descriptions were requested.
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_classinfo_seq_raw")]
+    pub descriptions: HashMap<ClassInfoClass, String>,
+}
+
+/// The response from IEconService/GetTradeStatus.
+#[derive(Deserialize, Debug)]
+pub struct GetTradeStatusResponse {
+    #[serde(default)]
+    pub trades: Vec<raw::RawTrade>,
+    /// Raw classinfos for the items in the trade, if descriptions were requested.
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_classinfo_seq_raw")]
+    pub descriptions: HashMap<ClassInfoClass, String>,
 }
 
+/// The response from IEconService/GetTradeHistory.
+#[derive(Deserialize, Debug)]
+pub struct GetTradeHistoryResponse {
+    #[serde(default)]
+    pub trades: Vec<raw::RawTrade>,
+    #[serde(default)]
+    pub more: bool,
+    pub total_trades: Option<u32>,
+    /// Raw classinfos for the items in the trades, if descriptions were requested.
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_classinfo_seq_raw")]
+    pub descriptions: HashMap<ClassInfoClass, String>,
+}
+
+/// A trade hold duration from IEconService/GetTradeHoldDurations.
+#[derive(Deserialize, Debug)]
+pub struct TradeHoldDuration {
+    #[serde(default)]
+    pub escrow_end_duration_seconds: u32,
+}
+
+/// The response from IEconService/GetTradeHoldDurations.
+#[derive(Deserialize, Debug)]
+pub struct GetTradeHoldDurationsResponse {
+    pub my_escrow: TradeHoldDuration,
+    pub their_escrow: TradeHoldDuration,
+    pub both_escrow: Option<TradeHoldDuration>,
+}
+
+/// The response from an inventory.
 #[derive(Deserialize, Debug)]
 pub struct GetInventoryResponse {
     #[serde(default)]
@@ -99,6 +159,7 @@ pub struct GetInventoryResponse {
     pub last_assetid: Option<u64>,
 }
 
+/// The response from an inventory using the older inventory endpoint.
 #[derive(Deserialize, Debug)]
 pub struct GetInventoryOldResponse {
     #[serde(default)]
@@ -116,6 +177,21 @@ pub struct GetInventoryOldResponse {
     pub descriptions: HashMap<ClassInfoAppClass, Arc<response::classinfo::ClassInfo>>,
 }
 
+/// The response from `/tradeoffer/{tradeofferid}/decline`.
+#[derive(Deserialize, Debug)]
+pub struct DeclineOfferResponse {
+    #[serde(with = "string")]
+    pub tradeofferid: TradeOfferId,
+}
+
+/// The response from `/tradeoffer/{tradeofferid}/cancel`.
+#[derive(Deserialize, Debug)]
+pub struct CancelOfferResponse {
+    #[serde(with = "string")]
+    pub tradeofferid: TradeOfferId,
+}
+
+/// The response from ISteamEconomy/GetAssetClassInfo.
 #[derive(Deserialize, Debug)]
 pub struct GetAssetClassInfoResponse {
     #[serde(deserialize_with = "deserialize_classinfo_map_raw")]
@@ -137,10 +213,50 @@ mod tests {
     
     #[test]
     fn parses_get_trade_offers_response() {
-        let response: GetTradeOffersResponse = serde_json::from_str(include_str!("fixtures/get_trade_offers.json")).unwrap();
+        let response: WebAPIResponse<GetTradeOffersResponse> = serde_json::from_str(include_str!("fixtures/get_trade_offers.json")).unwrap();
         let offer = response.response.trade_offers_sent.first().unwrap();
 
         assert_eq!(offer.message, Some(String::from("give me that key")));
+        
+        let classinfo_string = response.response.descriptions.get(&(440, 237182229, Some(73651412))).unwrap();
+        let parsed = serde_json::from_str::<response::classinfo::ClassInfo>(classinfo_string).unwrap();
+        
+        assert!(parsed.tradable);
+    }
+    
+    #[test]
+    fn parses_get_trade_status_response() {
+        let response: WebAPIResponse<GetTradeStatusResponse> = serde_json::from_str(include_str!("fixtures/get_trade_status.json")).unwrap();
+        let trade = response.response.trades.first().unwrap();
+        let asset = trade.assets_received.first().unwrap();
+        
+        assert_eq!(trade.status, crate::enums::TradeStatus::Complete);
+        assert_eq!(asset.new_assetid, Some(11292488054));
+        assert!(response.response.descriptions.contains_key(&(440, 101785959, Some(11040578))));
+    }
+    
+    #[test]
+    fn parses_get_trade_history_response() {
+        let response: WebAPIResponse<GetTradeHistoryResponse> = serde_json::from_str(include_str!("fixtures/get_trade_history.json")).unwrap();
+        let trade = response.response.trades.last().unwrap();
+        
+        assert_eq!(response.response.total_trades, Some(2));
+        assert!(!response.response.more);
+        assert_eq!(trade.status, crate::enums::TradeStatus::FullSupportRollback);
+    }
+    
+    #[test]
+    fn parses_get_trade_hold_durations_response() {
+        let response: WebAPIResponse<GetTradeHoldDurationsResponse> = serde_json::from_str(r#"{
+            "response": {
+                "my_escrow": { "escrow_end_duration_seconds": 0 },
+                "their_escrow": { "escrow_end_duration_seconds": 1296000 },
+                "both_escrow": { "escrow_end_duration_seconds": 1296000 }
+            }
+        }"#).unwrap();
+        
+        assert_eq!(response.response.my_escrow.escrow_end_duration_seconds, 0);
+        assert_eq!(response.response.their_escrow.escrow_end_duration_seconds, 1296000);
     }
     
     #[test]
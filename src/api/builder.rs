@@ -0,0 +1,107 @@
+use super::SteamTradeOfferAPI;
+use crate::{
+    SteamID,
+    ClassInfoCache,
+    RateLimiter,
+    RetryPolicy,
+    classinfo_cache::{ClassInfoStore, NoDiskClassInfoStore, LanguageFallback},
+};
+use std::sync::{Arc, Mutex};
+use reqwest::cookie::Jar;
+
+/// Builder for [`SteamTradeOfferAPI`].
+#[derive(Debug)]
+pub struct SteamTradeOfferAPIBuilder {
+    pub steamid: SteamID,
+    pub key: String,
+    pub identity_secret: Option<String>,
+    /// The cookie jar used for requests. A new jar is used by default.
+    pub cookies: Arc<Jar>,
+    pub language: String,
+    pub classinfo_cache: Arc<Mutex<ClassInfoCache>>,
+    /// Where classinfos are stored outside of memory. Classinfos are not stored by default.
+    pub classinfo_store: Arc<dyn ClassInfoStore>,
+    /// Whether cached classinfos in other languages can be used. Only classinfos in the
+    /// client's language are used by default.
+    pub classinfo_language_fallback: LanguageFallback,
+    /// Whether classinfos are requested along with offers rather than requested separately.
+    /// Disabled by default.
+    pub get_descriptions: bool,
+    /// Limits requests per host. Requests are not limited by default.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// How failed GET requests are retried. Uses [`RetryPolicy::default`] by default.
+    pub retry_policy: RetryPolicy,
+}
+
+impl SteamTradeOfferAPIBuilder {
+    pub fn new(steamid: SteamID, key: String) -> Self {
+        Self {
+            steamid,
+            key,
+            identity_secret: None,
+            cookies: Arc::new(Jar::default()),
+            language: String::from("english"),
+            classinfo_cache: Arc::new(Mutex::new(ClassInfoCache::default())),
+            classinfo_store: Arc::new(NoDiskClassInfoStore),
+            classinfo_language_fallback: LanguageFallback::default(),
+            get_descriptions: false,
+            rate_limiter: None,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+    
+    pub fn identity_secret(mut self, identity_secret: String) -> Self {
+        self.identity_secret = Some(identity_secret);
+        self
+    }
+    
+    /// Shares cookies with the given cookie jar.
+    pub fn cookies(mut self, cookies: Arc<Jar>) -> Self {
+        self.cookies = cookies;
+        self
+    }
+    
+    pub fn language(mut self, language: String) -> Self {
+        self.language = language;
+        self
+    }
+    
+    pub fn classinfo_cache(mut self, classinfo_cache: Arc<Mutex<ClassInfoCache>>) -> Self {
+        self.classinfo_cache = classinfo_cache;
+        self
+    }
+    
+    /// Sets the store used for classinfos.
+    pub fn classinfo_store(mut self, classinfo_store: Arc<dyn ClassInfoStore>) -> Self {
+        self.classinfo_store = classinfo_store;
+        self
+    }
+    
+    /// Sets when cached classinfos in other languages can be used.
+    pub fn classinfo_language_fallback(mut self, classinfo_language_fallback: LanguageFallback) -> Self {
+        self.classinfo_language_fallback = classinfo_language_fallback;
+        self
+    }
+    
+    /// Requests classinfos along with offers when getting offers.
+    pub fn get_descriptions(mut self, get_descriptions: bool) -> Self {
+        self.get_descriptions = get_descriptions;
+        self
+    }
+    
+    /// Limits requests per host using the given [`RateLimiter`].
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+    
+    /// Sets how failed GET requests are retried.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+    
+    pub fn build(self) -> SteamTradeOfferAPI {
+        SteamTradeOfferAPI::from(self)
+    }
+}
@@ -0,0 +1,149 @@
+use crate::types::{AppId, ClassInfoAppClass, ClassInfoClass, ClassInfoMap};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::watch;
+
+/// Receives the classinfos from a request once it completes. The value is `None` until the
+/// request completes. If the request fails the sender is dropped without sending a value.
+pub type ClassInfoRequestReceiver = watch::Receiver<Option<Arc<ClassInfoMap>>>;
+
+/// Tracks classinfo requests that are in flight so that concurrent requests for the same class
+/// share one request.
+#[derive(Debug, Default)]
+pub struct ClassInfoRequests {
+    map: Mutex<HashMap<ClassInfoClass, ClassInfoRequestReceiver>>,
+}
+
+impl ClassInfoRequests {
+    /// Splits classes into new requests of up to `chunk_size` classes for each app, and
+    /// receivers for the classes that are already being requested.
+    pub fn claim(
+        &self,
+        classes: HashSet<ClassInfoClass>,
+        chunk_size: usize,
+    ) -> (Vec<ClassInfoRequest<'_>>, Vec<(ClassInfoClass, ClassInfoRequestReceiver)>) {
+        let mut map = self.map.lock().unwrap();
+        let mut apps: HashMap<AppId, Vec<ClassInfoAppClass>> = HashMap::new();
+        let mut pending = Vec::new();
+        
+        for class in classes {
+            if let Some(receiver) = map.get(&class) {
+                pending.push((class, receiver.clone()));
+            } else {
+                let (appid, classid, instanceid) = class;
+                
+                apps.entry(appid)
+                    .or_default()
+                    .push((classid, instanceid));
+            }
+        }
+        
+        let mut requests = Vec::new();
+        
+        for (appid, classes) in apps {
+            for chunk in classes.chunks(chunk_size) {
+                let (sender, receiver) = watch::channel(None);
+                
+                for (classid, instanceid) in chunk {
+                    map.insert((appid, *classid, *instanceid), receiver.clone());
+                }
+                
+                requests.push(ClassInfoRequest {
+                    appid,
+                    classes: chunk.to_vec(),
+                    sender,
+                    requests: self,
+                });
+            }
+        }
+        
+        (requests, pending)
+    }
+}
+
+/// A claimed request for a chunk of classes. The classes are released when this is dropped,
+/// and anything waiting on the request is notified.
+#[derive(Debug)]
+pub struct ClassInfoRequest<'a> {
+    pub appid: AppId,
+    pub classes: Vec<ClassInfoAppClass>,
+    sender: watch::Sender<Option<Arc<ClassInfoMap>>>,
+    requests: &'a ClassInfoRequests,
+}
+
+impl<'a> ClassInfoRequest<'a> {
+    /// Completes the request, sending the classinfos to anything waiting on it.
+    pub fn complete(self, classinfos: Arc<ClassInfoMap>) {
+        // there may be nothing waiting on it
+        let _ = self.sender.send(Some(classinfos));
+    }
+}
+
+impl<'a> Drop for ClassInfoRequest<'a> {
+    fn drop(&mut self) {
+        let mut map = self.requests.map.lock().unwrap();
+        
+        for (classid, instanceid) in &self.classes {
+            map.remove(&(self.appid, *classid, *instanceid));
+        }
+    }
+}
+
+/// Waits for a pending request to complete. Returns `None` if the request failed.
+pub async fn wait_for_request(
+    mut receiver: ClassInfoRequestReceiver,
+) -> Option<Arc<ClassInfoMap>> {
+    receiver.wait_for(|classinfos| classinfos.is_some()).await
+        .ok()
+        .and_then(|classinfos| classinfos.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn shares_requests_for_the_same_class() {
+        let requests = ClassInfoRequests::default();
+        let class = (440, 101785959, Some(11040578));
+        let (claimed, pending) = requests.claim(HashSet::from([class]), 100);
+        
+        assert_eq!(claimed.len(), 1);
+        assert!(pending.is_empty());
+        
+        let (other_claimed, mut other_pending) = requests.claim(HashSet::from([class, (440, 1, None)]), 100);
+        
+        // the class already being requested is pending, the other is claimed
+        assert_eq!(other_claimed.len(), 1);
+        assert_eq!(other_pending.len(), 1);
+        
+        let (_class, receiver) = other_pending.remove(0);
+        
+        claimed.into_iter().for_each(|request| request.complete(Arc::new(ClassInfoMap::new())));
+        
+        assert!(wait_for_request(receiver).await.is_some());
+        
+        drop(other_claimed);
+        
+        // both requests are complete so the classes can be claimed again
+        let (claimed, pending) = requests.claim(HashSet::from([class, (440, 1, None)]), 100);
+        
+        assert_eq!(claimed.len(), 1);
+        assert!(pending.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn notifies_when_request_fails() {
+        let requests = ClassInfoRequests::default();
+        let class = (440, 101785959, Some(11040578));
+        let (claimed, _pending) = requests.claim(HashSet::from([class]), 100);
+        let (_claimed, mut pending) = requests.claim(HashSet::from([class]), 100);
+        let (_class, receiver) = pending.remove(0);
+        
+        drop(claimed);
+        
+        assert!(wait_for_request(receiver).await.is_none());
+    }
+}
@@ -1,11 +1,14 @@
-use super::raw;
-use lazy_regex::Regex;
+use super::{raw, api_response::GetInventoryOldResponse};
+use lazy_regex::{Regex, regex_captures};
+use scraper::{Html, Selector};
+use serde::Deserialize;
 use std::sync::Arc;
+use log::warn;
 use crate::{
     SteamID,
-    error::MissingClassInfoError,
-    types::ClassInfoMap,
-    response,
+    error::{MissingClassInfoError, ParseHtmlError},
+    types::{AppId, ContextId, ClassInfoMap, Inventory, TradeOfferId},
+    response::{self, deserializers::hashmap_or_vec},
 };
 
 pub fn from_raw_receipt_asset(
@@ -33,16 +36,44 @@ pub fn from_raw_trade_offer(
     offer: raw::RawTradeOffer,
     map: &ClassInfoMap,
 ) -> Result<response::trade_offer::TradeOffer, MissingClassInfoError> {
-    fn collect_items(assets: Vec<raw::RawAsset>, map: &ClassInfoMap) -> Result<Vec<response::asset::Asset>, MissingClassInfoError> {
+    fn collect_items(
+        tradeofferid: TradeOfferId,
+        assets: Vec<raw::RawOfferItem>,
+        map: &ClassInfoMap,
+    ) -> Result<(Vec<response::asset::Asset>, Vec<response::currency::Currency>), MissingClassInfoError> {
         let mut items = Vec::new();
+        let mut currencies = Vec::new();
         
         for asset in assets {
+            let assetid = match (asset.assetid, asset.currencyid) {
+                (Some(assetid), _) => assetid,
+                (None, Some(currencyid)) => {
+                    currencies.push(response::currency::Currency {
+                        appid: asset.appid,
+                        contextid: asset.contextid,
+                        currencyid,
+                        amount: asset.amount,
+                    });
+                    continue;
+                },
+                // neither an asset nor a currency
+                (None, None) => {
+                    warn!(
+                        "Skipping item {}:{} in offer {} without an assetid or currencyid",
+                        asset.appid,
+                        asset.classid,
+                        tradeofferid,
+                    );
+                    continue;
+                },
+            };
+            
             if let Some(classinfo) = map.get(&(asset.appid, asset.classid, asset.instanceid)) {
                 items.push(response::asset::Asset {
                     classinfo: Arc::clone(classinfo),
                     appid: asset.appid,
                     contextid: asset.contextid,
-                    assetid: asset.assetid,
+                    assetid,
                     amount: asset.amount,
                 });
             } else {
@@ -55,7 +86,7 @@ pub fn from_raw_trade_offer(
             }
         }
         
-        Ok(items)
+        Ok((items, currencies))
     }
     
     fn steamid_from_accountid(accountid: u32) -> SteamID {
@@ -67,12 +98,14 @@ pub fn from_raw_trade_offer(
         )
     }
     
-    let items_to_give = collect_items(offer.items_to_give, map)?;
-    let items_to_receive = collect_items(offer.items_to_receive, map)?;
+    let (items_to_give, currency_to_give) = collect_items(offer.tradeofferid, offer.items_to_give, map)?;
+    let (items_to_receive, currency_to_receive) = collect_items(offer.tradeofferid, offer.items_to_receive, map)?;
     
     Ok(response::trade_offer::TradeOffer {
         items_to_give,
         items_to_receive,
+        currency_to_give,
+        currency_to_receive,
         tradeofferid: offer.tradeofferid,
         tradeid: offer.tradeid,
         trade_offer_state: offer.trade_offer_state,
@@ -88,6 +121,166 @@ pub fn from_raw_trade_offer(
     })
 }
 
+pub fn from_raw_trade(
+    trade: raw::RawTrade,
+    map: &ClassInfoMap,
+) -> Result<response::trade::Trade, MissingClassInfoError> {
+    fn collect_items(assets: Vec<raw::RawTradeAsset>, map: &ClassInfoMap) -> Result<Vec<response::trade::TradeAsset>, MissingClassInfoError> {
+        let mut items = Vec::new();
+        
+        for asset in assets {
+            if let Some(classinfo) = map.get(&(asset.appid, asset.classid, asset.instanceid)) {
+                items.push(response::trade::TradeAsset {
+                    classinfo: Arc::clone(classinfo),
+                    appid: asset.appid,
+                    contextid: asset.contextid,
+                    assetid: asset.assetid,
+                    amount: asset.amount,
+                    new_assetid: asset.new_assetid,
+                    new_contextid: asset.new_contextid,
+                });
+            } else {
+                return Err(MissingClassInfoError {
+                    appid: asset.appid,
+                    classid: asset.classid,
+                    instanceid: asset.instanceid,
+                });
+            }
+        }
+        
+        Ok(items)
+    }
+    
+    Ok(response::trade::Trade {
+        assets_received: collect_items(trade.assets_received, map)?,
+        assets_given: collect_items(trade.assets_given, map)?,
+        tradeid: trade.tradeid,
+        partner: SteamID::from(trade.steamid_other),
+        status: trade.status,
+        time_init: trade.time_init,
+        time_escrow_end: trade.time_escrow_end,
+    })
+}
+
+/// Collects the items from responses from the older inventory endpoint.
+pub fn from_old_inventory_responses(
+    responses: Vec<GetInventoryOldResponse>,
+    appid: AppId,
+    contextid: ContextId,
+) -> Result<Inventory, MissingClassInfoError> {
+    let mut inventory: Inventory = Vec::new();
+    
+    for body in responses {
+        for item in body.assets.values() {
+            if let Some(classinfo) = body.descriptions.get(&(item.classid, item.instanceid)) {
+                inventory.push(response::asset::Asset {
+                    classinfo: Arc::clone(classinfo),
+                    appid,
+                    contextid,
+                    assetid: item.assetid,
+                    amount: item.amount,
+                });
+            } else {
+                return Err(MissingClassInfoError {
+                    appid,
+                    classid: item.classid,
+                    instanceid: item.instanceid,
+                });
+            }
+        }
+    }
+    
+    Ok(inventory)
+}
+
+/// Parses the details for trading with a partner from the trade offer page.
+pub fn parse_user_details(
+    body: &str,
+) -> Result<response::user_details::UserDetails, ParseHtmlError> {
+    #[derive(Deserialize)]
+    struct AppContexts(
+        #[serde(deserialize_with = "hashmap_or_vec")]
+        Vec<response::user_details::AppContextData>,
+    );
+    
+    fn get_days(group: Option<(&str, &str)>) -> u32 {
+        match group {
+            Some((_, days_str)) => days_str.parse::<u32>().unwrap_or_default(),
+            None => 0,
+        }
+    }
+    
+    fn get_bool(group: Option<(&str, &str)>) -> Option<bool> {
+        group.map(|(_, value)| value == "true")
+    }
+    
+    fn get_string(group: Option<(&str, &str)>) -> Option<String> {
+        // the value is a JSON-encoded string
+        group.and_then(|(_, value)| serde_json::from_str::<String>(value).ok())
+    }
+    
+    fn get_app_contexts(group: Option<(&str, &str)>) -> Result<Vec<response::user_details::AppContextData>, ParseHtmlError> {
+        match group {
+            Some((_, json)) => serde_json::from_str::<AppContexts>(json)
+                .map(|app_contexts| app_contexts.0)
+                .map_err(|_| ParseHtmlError::Malformed("Invalid app context data")),
+            None => Ok(Vec::new()),
+        }
+    }
+    
+    fn get_avatar_url(fragment: &Html, selector: &str) -> Option<String> {
+        let selector = Selector::parse(selector).ok()?;
+        
+        fragment.select(&selector)
+            .next()
+            .and_then(|element| element.value().attr("src"))
+            .map(|src| src.to_string())
+    }
+    
+    let app_context_data = regex_captures!(r#"var g_rgAppContextData = (.*);"#, body)
+        .ok_or(ParseHtmlError::Malformed("Missing app context data"))?;
+    let my_escrow = get_days(regex_captures!(r#"var g_daysMyEscrow = (\d+);"#, body));
+    let them_escrow = get_days(regex_captures!(r#"var g_daysTheirEscrow = (\d+);"#, body));
+    let fragment = Html::parse_document(body);
+    let my_app_contexts = get_app_contexts(Some(app_context_data))?;
+    let their_app_contexts = get_app_contexts(regex_captures!(r#"var g_rgPartnerAppContextData = (.*);"#, body))?;
+    let me = response::user_details::TradeUserDetails {
+        persona_name: get_string(regex_captures!(r#"var g_strYourPersonaName = (".*");"#, body))
+            .unwrap_or_default(),
+        avatar_url: get_avatar_url(&fragment, "#trade_yours .avatarIcon img"),
+        profile_private: get_bool(regex_captures!(r#"var g_bYourProfilePrivate = (true|false);"#, body)),
+        inventory_private: get_bool(regex_captures!(r#"var g_bYourInventoryPrivate = (true|false);"#, body)),
+        app_contexts: my_app_contexts,
+    };
+    let them = response::user_details::TradeUserDetails {
+        persona_name: get_string(regex_captures!(r#"var g_strTradePartnerPersonaName = (".*");"#, body))
+            .unwrap_or_default(),
+        avatar_url: get_avatar_url(&fragment, "#trade_theirs .avatarIcon img"),
+        profile_private: get_bool(regex_captures!(r#"var g_bTradePartnerProfilePrivate = (true|false);"#, body)),
+        // contexts are also omitted when the partner's inventory is public but empty
+        inventory_private: get_bool(regex_captures!(r#"var g_bTradePartnerInventoryPrivate = (true|false);"#, body)),
+        app_contexts: their_app_contexts,
+    };
+    
+    Ok(response::user_details::UserDetails {
+        my_escrow,
+        them_escrow,
+        me: Some(me),
+        them: Some(them),
+    })
+}
+
+/// Parses our trade URL from the trade offer privacy page.
+pub fn parse_trade_url(body: &str) -> Option<String> {
+    let fragment = Html::parse_document(body);
+    let selector = Selector::parse("#trade_offer_access_url").ok()?;
+    
+    fragment.select(&selector)
+        .next()
+        .and_then(|element| element.value().attr("value"))
+        .map(|value| value.to_string())
+}
+
 pub fn parse_receipt_script(
     script: &str,
 ) -> Result<Vec<raw::RawReceiptAsset>, &'static str> {
@@ -137,4 +330,70 @@ mod tests {
         
         assert_eq!(scripts.len(), 2);
     }
+    
+    #[test]
+    fn collects_currency_from_raw_trade_offer() {
+        let raw_offer: raw::RawTradeOffer = serde_json::from_str(r#"{
+            "tradeofferid": "4990667216",
+            "accountid_other": 119913840,
+            "expiration_time": 1643161029,
+            "trade_offer_state": 2,
+            "items_to_give": [
+                {
+                    "appid": 753,
+                    "contextid": "4",
+                    "currencyid": "2001",
+                    "classid": "3188",
+                    "amount": "150"
+                }
+            ],
+            "is_our_offer": true,
+            "time_created": 1641951429,
+            "time_updated": 1641951438,
+            "from_real_time_trade": false,
+            "escrow_end_date": 0,
+            "confirmation_method": 0
+        }"#).unwrap();
+        let offer = from_raw_trade_offer(raw_offer, &ClassInfoMap::new()).unwrap();
+        let currency = offer.currency_to_give.first().unwrap();
+        
+        assert!(offer.items_to_give.is_empty());
+        assert_eq!(currency.currencyid, 2001);
+        assert_eq!(currency.amount, 150);
+    }
+    
+    #[test]
+    fn parses_user_details() {
+        let user_details = parse_user_details(include_str!("fixtures/trade_offer_new.html")).unwrap();
+        let me = user_details.me.unwrap();
+        let them = user_details.them.unwrap();
+        let tf2 = me.get_app(440).unwrap();
+        
+        assert_eq!(user_details.my_escrow, 0);
+        assert_eq!(user_details.them_escrow, 15);
+        assert_eq!(me.persona_name, "Me");
+        assert_eq!(them.persona_name, "Partner \"quoted\"");
+        assert_eq!(them.avatar_url.as_deref(), Some("https://avatars.akamai.steamstatic.com/partner_medium.jpg"));
+        assert_eq!(them.profile_private, Some(false));
+        assert_eq!(tf2.asset_count, 1082);
+        assert_eq!(tf2.get_context(2).unwrap().asset_count, 1082);
+        assert!(them.app_contexts.is_empty());
+        assert_eq!(them.inventory_private, Some(false));
+    }
+    
+    #[test]
+    fn parses_trade_url() {
+        let trade_url = parse_trade_url(include_str!("fixtures/trade_offer_privacy.html")).unwrap();
+        
+        assert_eq!(trade_url, "https://steamcommunity.com/tradeoffer/new/?partner=120913840&token=SCRXNW6Q");
+    }
+    
+    #[test]
+    fn parses_private_partner_inventory() {
+        let body = include_str!("fixtures/trade_offer_new.html")
+            .replace("g_bTradePartnerInventoryPrivate = false", "g_bTradePartnerInventoryPrivate = true");
+        let them = parse_user_details(&body).unwrap().them.unwrap();
+        
+        assert_eq!(them.inventory_private, Some(true));
+    }
 }
\ No newline at end of file
@@ -1,18 +1,55 @@
-mod raw;
-mod api_response;
+pub mod raw;
+pub mod api_request;
+pub mod api_response;
+
+mod builder;
 mod helpers;
+mod classinfo_requests;
+
+pub use builder::SteamTradeOfferAPIBuilder;
 
 use helpers::{
     parse_receipt_script,
     from_raw_trade_offer,
+    from_raw_trade,
+    parse_user_details,
+    parse_trade_url,
+    from_old_inventory_responses,
     from_raw_receipt_asset,
 };
+use classinfo_requests::{ClassInfoRequests, ClassInfoRequest, wait_for_request};
+use api_request::{
+    GetTradeOffersRequest,
+    GetTradeOfferRequest,
+    GetTradeStatusRequest,
+    GetTradeHistoryRequest,
+    GetTradeOffersSummaryRequest,
+    GetTradeHoldDurationsRequest,
+    TradeOfferPageQuery,
+    OfferForm,
+    OfferFormUser,
+    TradeOfferCreateParams,
+    SendOfferRequest,
+    AcceptOfferRequest,
+    DeclineOfferRequest,
+    CancelOfferRequest,
+    NewTradeUrlRequest,
+    GetInventoryRequest,
+    GetInventoryOldRequest,
+    GetPartnerInventoryRequest,
+};
 use api_response::{
-    GetTradeOffersResponseBody,
+    WebAPIResponse,
     GetTradeOffersResponse,
+    GetTradeOfferResponse,
+    GetTradeStatusResponse,
+    GetTradeHistoryResponse,
+    GetTradeHoldDurationsResponse,
     GetInventoryResponse,
     GetInventoryOldResponse,
     GetAssetClassInfoResponse,
+    DeclineOfferResponse,
+    CancelOfferResponse,
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -22,8 +59,10 @@ use crate::{
     error::Error,
     enums::OfferFilter,
     SteamID,
+    TradeUrl,
     time::{ServerTime, get_system_time},
-    classinfo_cache::{ClassInfoCache, helpers as classinfo_cache_helpers},
+    classinfo_cache::{ClassInfoCache, ClassInfoStore, LanguageFallback},
+    middleware::MiddlewareOptions,
     types::{
         ClassInfoMap,
         ClassInfoAppClass,
@@ -36,21 +75,29 @@ use crate::{
         Client,
     },
     response,
-    request::{self, serializers::steamid_as_string},
-    serializers::string,
-    helpers::{get_default_middleware, parses_response},
+    request,
+    helpers::{get_default_middleware, check_response, parses_response},
 };
-use serde::{Deserialize, Serialize};
+use serde::{Serialize, de::DeserializeOwned};
 use reqwest::cookie::Jar;
 use url::{Url, ParseError};
 use reqwest::header::REFERER;
 use lazy_regex::{regex_captures, regex_is_match};
+use log::warn;
+use futures::stream::{self, StreamExt};
 
 const HOSTNAME: &str = "https://steamcommunity.com";
 const API_HOSTNAME: &str = "https://api.steampowered.com";
 const USER_AGENT_STRING: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/97.0.4692.71 Safari/537.36";
 const ONE_YEAR_SECS: u64 = 31536000;
+const ONE_DAY_SECS: u32 = 86400;
+/// The maximum number of classes requested at once from GetAssetClassInfo.
+const CLASSINFO_CHUNK_SIZE: usize = 100;
+/// The maximum number of GetAssetClassInfo requests made in parallel.
+const CLASSINFO_CHUNK_CONCURRENCY: usize = 4;
 
+/// The low-level client used by [`TradeOfferManager`](crate::TradeOfferManager) for making
+/// requests to Steam. Use this directly for requests the manager doesn't cover.
 #[derive(Debug)]
 pub struct SteamTradeOfferAPI {
     client: Client,
@@ -61,28 +108,42 @@ pub struct SteamTradeOfferAPI {
     pub identity_secret: Option<String>,
     pub sessionid: Arc<RwLock<Option<String>>>,
     pub classinfo_cache: Arc<Mutex<ClassInfoCache>>,
+    pub classinfo_store: Arc<dyn ClassInfoStore>,
+    pub classinfo_language_fallback: LanguageFallback,
+    pub get_descriptions: bool,
+    classinfo_requests: ClassInfoRequests,
 }
 
-impl SteamTradeOfferAPI {
-    pub fn new(
-        cookies: Arc<Jar>,
-        steamid: SteamID,
-        key: String,
-        language: String,
-        identity_secret: Option<String>,
-        classinfo_cache: Arc<Mutex<ClassInfoCache>>,
-    ) -> Self {
+impl From<SteamTradeOfferAPIBuilder> for SteamTradeOfferAPI {
+    fn from(builder: SteamTradeOfferAPIBuilder) -> Self {
+        let cookies = builder.cookies;
+        let middleware_options = MiddlewareOptions {
+            rate_limiter: builder.rate_limiter,
+            retry_policy: builder.retry_policy,
+        };
+        
         Self {
-            client: get_default_middleware(Arc::clone(&cookies), USER_AGENT_STRING),
-            key,
-            steamid,
-            identity_secret,
-            language,
-            cookies: Arc::clone(&cookies),
+            client: get_default_middleware(Arc::clone(&cookies), USER_AGENT_STRING, &middleware_options),
+            key: builder.key,
+            language: builder.language,
+            steamid: builder.steamid,
+            identity_secret: builder.identity_secret,
+            cookies,
             sessionid: Arc::new(RwLock::new(None)),
-            classinfo_cache,
+            classinfo_cache: builder.classinfo_cache,
+            classinfo_store: builder.classinfo_store,
+            classinfo_language_fallback: builder.classinfo_language_fallback,
+            get_descriptions: builder.get_descriptions,
+            classinfo_requests: ClassInfoRequests::default(),
         }
     }
+}
+
+impl SteamTradeOfferAPI {
+    /// Builder for a new client.
+    pub fn builder(steamid: SteamID, key: String) -> SteamTradeOfferAPIBuilder {
+        SteamTradeOfferAPIBuilder::new(steamid, key)
+    }
     
     fn get_uri(
         &self,
@@ -110,6 +171,7 @@ impl SteamTradeOfferAPI {
         Ok(())
     }
     
+    /// Sets the session ID and cookies used for requests to steamcommunity.com.
     pub fn set_session(
         &self,
         sessionid: &str,
@@ -124,52 +186,18 @@ impl SteamTradeOfferAPI {
         Ok(())
     }
     
+    /// Sends an offer. If `counter_tradeofferid` is given the offer is sent as a counter offer.
     pub async fn send_offer(
         &self,
         offer: &request::trade_offer::NewTradeOffer,
         counter_tradeofferid: Option<TradeOfferId>,
     ) -> Result<response::sent_offer::SentOffer, Error> {
-        #[derive(Serialize, Debug)]
-        struct OfferFormUser<'b> {
-            assets: &'b Vec<request::trade_offer::Item>,
-            currency: Vec<response::Currency>,
-            ready: bool,
-        }
-
-        #[derive(Serialize, Debug)]
-        struct OfferForm<'b> {
-            newversion: bool,
-            version: u32,
-            me: OfferFormUser<'b>,
-            them: OfferFormUser<'b>,
-        }
-
-        #[derive(Serialize, Debug)]
-        struct TradeOfferCreateParams<'b> {
-            #[serde(skip_serializing_if = "Option::is_none")]
-            trade_offer_access_token: &'b Option<String>,
-        }
-
-        #[derive(Serialize, Debug)]
-        struct SendOfferParams<'a, 'b> {
-            sessionid: &'a String,
-            serverid: u32,
-            json_tradeoffer: String,
-            tradeoffermessage: &'b Option<String>,
-            captcha: &'static str,
-            trade_offer_create_params: String,
-            tradeofferid_countered: &'b Option<u64>,
-            #[serde(serialize_with = "steamid_as_string")]
-            partner: &'b SteamID,
-        }
-        
-        #[derive(Serialize, Debug)]
-        struct RefererParams<'b> {
-            partner: u32,
-            token: &'b Option<String>,
-        }
-        
-        let num_items: usize = offer.items_to_give.len() + offer.items_to_receive.len();
+        let num_items: usize = {
+            offer.items_to_give.len() +
+            offer.items_to_receive.len() +
+            offer.currency_to_give.len() +
+            offer.currency_to_receive.len()
+        };
 
         if num_items == 0 {
             return Err(Error::Parameter("Cannot send an empty offer"));
@@ -186,7 +214,7 @@ impl SteamTradeOfferAPI {
                 Some(id) => id.to_string(),
                 None => String::from("new"),
             };
-            let qs_params = serde_qs::to_string(&RefererParams {
+            let qs_params = serde_qs::to_string(&TradeOfferPageQuery {
                 partner: offer.partner.account_id(),
                 token: &offer.token,
             })?;
@@ -204,12 +232,12 @@ impl SteamTradeOfferAPI {
                 version: num_items as u32 + 1,
                 me: OfferFormUser {
                     assets: &offer.items_to_give,
-                    currency: Vec::new(),
+                    currency: &offer.currency_to_give,
                     ready: false,
                 },
                 them: OfferFormUser {
                     assets: &offer.items_to_receive,
-                    currency: Vec::new(),
+                    currency: &offer.currency_to_receive,
                     ready: false,
                 },
             })?;
@@ -217,7 +245,7 @@ impl SteamTradeOfferAPI {
                 trade_offer_access_token: &offer.token,
             })?;
             
-            SendOfferParams {
+            SendOfferRequest {
                 // presence of sessionid was checked above - unwrap is safe here
                 sessionid: &sessionid.unwrap(),
                 serverid: 1,
@@ -240,9 +268,33 @@ impl SteamTradeOfferAPI {
         Ok(body)
     }
     
+    /// Gets the items received in a trade. The new IDs of the items are taken from 
+    /// GetTradeStatus, falling back to the trade's receipt page if they aren't available.
     pub async fn get_receipt(
         &self,
         trade_id: &TradeId,
+    ) -> Result<Vec<response::asset::Asset>, Error> {
+        match self.get_trade_status(*trade_id).await {
+            Ok(trade) => {
+                let assets = trade.assets_received
+                    .iter()
+                    .map(|asset| asset.to_new_asset())
+                    .collect::<Option<Vec<_>>>();
+                
+                if let Some(assets) = assets {
+                    return Ok(assets);
+                }
+            },
+            Err(error) => warn!("Error getting trade status for trade {}: {}", trade_id, error),
+        }
+        
+        self.get_receipt_page(trade_id).await
+    }
+    
+    /// Gets the items received in a trade from its receipt page.
+    pub async fn get_receipt_page(
+        &self,
+        trade_id: &TradeId,
     ) -> Result<Vec<response::asset::Asset>, Error> {
         let uri = self.get_uri(&format!("/trade/{}/receipt", trade_id));
         let response = self.client.get(&uri)
@@ -280,6 +332,8 @@ impl SteamTradeOfferAPI {
         }
     }
     
+    /// Requests classinfos for up to 100 classes in an app from ISteamEconomy/GetAssetClassInfo.
+    /// Cached classinfos are not checked, but the result is added to the cache.
     pub async fn get_app_asset_classinfos_chunk(
         &self,
         appid: AppId,
@@ -309,169 +363,236 @@ impl SteamTradeOfferAPI {
             .send()
             .await?;
         let body: GetAssetClassInfoResponse = parses_response(response).await?;
-        let classinfos = body.result;
+        let classinfos = body.result
+            .into_iter()
+            .map(|((classid, instanceid), classinfo_string)| ((appid, classid, instanceid), classinfo_string))
+            .collect::<HashMap<_, _>>();
         
-        classinfo_cache_helpers::save_classinfos(appid, &classinfos).await;
+        self.save_classinfos(classinfos).await
+    }
+    
+    /// Saves raw classinfos from a response to the store and the cache.
+    async fn save_classinfos(
+        &self,
+        classinfos: HashMap<ClassInfoClass, String>,
+    ) -> Result<ClassInfoMap, Error> {
+        if let Err(error) = self.classinfo_store.save_classinfos(&self.language, &classinfos).await {
+            warn!("Error saving classinfos: {}", error);
+        }
         
         let classinfos = classinfos
             .into_iter()
-            .map(|((classid, instanceid), classinfo_string)| {
+            .map(|(class, classinfo_string)| {
                 serde_json::from_str::<response::ClassInfo>(&classinfo_string)
-                    .map(|classinfo| {
-                        (
-                            (appid, classid, instanceid),
-                            Arc::new(classinfo),
-                        )
-                    })
+                    .map(|classinfo| (class, Arc::new(classinfo)))
             })
             .collect::<Result<HashMap<_, _>, _>>()?;
         
-        self.classinfo_cache.lock().unwrap().insert_classinfos(&classinfos);
+        self.classinfo_cache.lock().unwrap().insert_classinfos(&self.language, &classinfos);
 
         Ok(classinfos)
     }
     
-    async fn get_app_asset_classinfos(
+    /// Requests the classinfos for a claimed request, passing them on to anything waiting on it.
+    async fn get_claimed_asset_classinfos(
         &self,
-        appid: AppId,
-        classes: Vec<ClassInfoAppClass>,
-    ) -> Result<Vec<ClassInfoMap>, Error> {
-        let chuck_size = 100;
-        let chunks = classes.chunks(chuck_size);
-        let mut maps = Vec::with_capacity(chunks.len());
+        request: ClassInfoRequest<'_>,
+    ) -> Result<Arc<ClassInfoMap>, Error> {
+        let classinfos = self.get_app_asset_classinfos_chunk(request.appid, &request.classes).await?;
+        let classinfos = Arc::new(classinfos);
+        
+        request.complete(Arc::clone(&classinfos));
+        Ok(classinfos)
+    }
+    
+    /// Moves classinfos in the given language that are cached in memory or in the store from
+    /// `needed` into `map`.
+    async fn load_cached_classinfos(
+        &self,
+        language: &str,
+        needed: &mut HashSet<ClassInfoClass>,
+        map: &mut ClassInfoMap,
+    ) {
+        {
+            // check memory for caches
+            let mut classinfo_cache = self.classinfo_cache.lock().unwrap();
+            
+            needed.retain(|class| {
+                if let Some(classinfo) = classinfo_cache.get_classinfo(language, class) {
+                    map.insert(*class, classinfo);
+                    // we don't need it
+                    false
+                } else {
+                    true
+                }
+            });
+            
+            // drop the lock
+        }
         
-        for chunk in chunks {
-            maps.push(self.get_app_asset_classinfos_chunk(appid, chunk).await?);
+        if needed.is_empty() {
+            return;
         }
         
-        Ok(maps)
+        // check store for caches
+        let needed_classes = needed
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        let results = self.classinfo_store.load_classinfos(language, &needed_classes).await;
+        
+        if !results.is_empty() {
+            let mut classinfo_cache = self.classinfo_cache.lock().unwrap();
+            
+            for (class, classinfo) in results {
+                let classinfo = Arc::new(classinfo);
+                
+                needed.remove(&class);
+                classinfo_cache.insert(language, class, Arc::clone(&classinfo));
+                map.insert(class, classinfo);
+            }
+            
+            // drop the lock
+        }
     }
     
+    /// Gets classinfos for the given classes, from the cache where available.
     pub async fn get_asset_classinfos(
         &self,
-        classes: &Vec<ClassInfoClass>,
+        classes: &[ClassInfoClass],
     ) -> Result<ClassInfoMap, Error> {
-        let mut apps: HashMap<AppId, Vec<ClassInfoAppClass>> = HashMap::new();
         let mut map: HashMap<ClassInfoClass, Arc<response::ClassInfo>> = HashMap::new();
-        let mut needed: HashSet<&ClassInfoClass> = HashSet::from_iter(classes.iter());
+        let mut needed: HashSet<ClassInfoClass> = HashSet::from_iter(classes.iter().copied());
         
         if classes.is_empty() {
             return Ok(map);
         }
         
-        {
-            {
-                // check memory for caches
-                let mut classinfo_cache = self.classinfo_cache.lock().unwrap();
-                
-                needed = needed
-                    .into_iter()
-                    .filter(|class| {
-                        if let Some(classinfo) = classinfo_cache.get_classinfo(class) {
-                            map.insert(**class, classinfo);
-                            // we don't need it
-                            false
-                        } else {
-                            true
-                        }
-                    })
-                    .collect::<HashSet<_>>();
-                
-                // drop the lock
+        self.load_cached_classinfos(&self.language, &mut needed, &mut map).await;
+        
+        if !needed.is_empty() && !self.classinfo_language_fallback.is_strict() {
+            let mut cached_languages = self.classinfo_cache.lock().unwrap().languages();
+            
+            if self.classinfo_language_fallback.allows_any() {
+                cached_languages.append(&mut self.classinfo_store.languages().await);
             }
             
-            if !needed.is_empty() {
-                // check filesystem for caches
-                let results = classinfo_cache_helpers::load_classinfos(&needed).await
-                    .into_iter()
-                    .flatten()
-                    .collect::<Vec<_>>();
-                
-                if !results.is_empty() {
-                    let mut classinfo_cache = self.classinfo_cache.lock().unwrap();
-                    
-                    for (class, classinfo) in results {
-                        let classinfo = Arc::new(classinfo);
-                        
-                        needed.remove(&class);
-                        classinfo_cache.insert(class, Arc::clone(&classinfo));
-                        map.insert(class, classinfo);
-                    }
+            let fallback_languages = self.classinfo_language_fallback
+                .fallback_languages(&self.language, &cached_languages);
             
-                    // drop the lock
+            for language in fallback_languages {
+                if needed.is_empty() {
+                    break;
                 }
-            }
-            
-            for (appid, classid, instanceid) in needed {
-                match apps.get_mut(appid) {
-                    Some(classes) => {
-                        classes.push((*classid, *instanceid));
-                    },
-                    None => {
-                        let classes = vec![(*classid, *instanceid)];
-                        
-                        apps.insert(*appid, classes);
-                    },
+                
+                let mut fallback_map = HashMap::new();
+                
+                self.load_cached_classinfos(&language, &mut needed, &mut fallback_map).await;
+                
+                for (class, classinfo) in fallback_map {
+                    map.insert(class, self.classinfo_language_fallback.apply(&language, classinfo));
                 }
             }
         }
         
-        for (appid, classes) in apps {
-            for maps in self.get_app_asset_classinfos(appid, classes).await? {
-                for (class, classinfo) in maps {
+        if needed.is_empty() {
+            return Ok(map);
+        }
+        
+        // classes already being requested elsewhere are waited on rather than requested again
+        let (requests, pending) = self.classinfo_requests.claim(needed, CLASSINFO_CHUNK_SIZE);
+        let mut futures = Vec::with_capacity(requests.len());
+        
+        for request in requests {
+            futures.push(self.get_claimed_asset_classinfos(request));
+        }
+        
+        let results = stream::iter(futures)
+            .buffer_unordered(CLASSINFO_CHUNK_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+        
+        for classinfos in results {
+            for (class, classinfo) in classinfos?.iter() {
+                map.insert(*class, Arc::clone(classinfo));
+            }
+        }
+        
+        let mut failed: HashMap<AppId, Vec<ClassInfoAppClass>> = HashMap::new();
+        
+        for (class, receiver) in pending {
+            let classinfo = wait_for_request(receiver).await
+                .and_then(|classinfos| classinfos.get(&class).cloned());
+            
+            match classinfo {
+                Some(classinfo) => {
                     map.insert(class, classinfo);
-                }
+                },
+                // the other request failed or did not include this class so this one makes its own
+                None => {
+                    let (appid, classid, instanceid) = class;
+                    
+                    failed.entry(appid)
+                        .or_default()
+                        .push((classid, instanceid));
+                },
             }
         }
         
+        let chunks = failed
+            .iter()
+            .flat_map(|(appid, classes)| {
+                classes
+                    .chunks(CLASSINFO_CHUNK_SIZE)
+                    .map(move |chunk| (*appid, chunk))
+            })
+            .collect::<Vec<_>>();
+        let mut futures = Vec::with_capacity(chunks.len());
+        
+        for (appid, chunk) in chunks {
+            futures.push(self.get_app_asset_classinfos_chunk(appid, chunk));
+        }
+        
+        let results = stream::iter(futures)
+            .buffer_unordered(CLASSINFO_CHUNK_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+        
+        for classinfos in results {
+            map.extend(classinfos?);
+        }
+        
         Ok(map)
     }
 
+    /// Gets offers with their items. Offers that are no longer active are only included if they
+    /// were updated after `historical_cutoff`.
     pub async fn get_trade_offers(
         &self,
         filter: &OfferFilter,
         historical_cutoff: &Option<ServerTime>,
     ) -> Result<Vec<response::trade_offer::TradeOffer>, Error> {
-        #[derive(Serialize, Debug)]
-        struct Form<'a> {
-            key: &'a str,
-            language: &'a str,
-            active_only: bool,
-            historical_only: bool,
-            get_sent_offers: bool,
-            get_received_offers: bool,
-            get_descriptions: bool,
-            time_historical_cutoff: u64,
-            cursor: Option<u32>,
-        }
-        
         let mut cursor = None;
-        let mut responses: Vec<GetTradeOffersResponseBody> = Vec::new();
+        let mut responses: Vec<GetTradeOffersResponse> = Vec::new();
         let time_historical_cutoff: u64 = match historical_cutoff {
             Some(cutoff) => cutoff.timestamp() as u64,
             None => get_system_time() + ONE_YEAR_SECS,
         };
-        let uri = self.get_api_url("IEconService", "GetTradeOffers", 1);
         
         loop {
-            let response = self.client.get(&uri)
-                .query(&Form {
-                    key: &self.key,
-                    language: &self.language,
-                    active_only: *filter == OfferFilter::ActiveOnly,
-                    historical_only: *filter == OfferFilter::HistoricalOnly,
-                    get_sent_offers: true,
-                    get_received_offers: true,
-                    get_descriptions: false,
-                    time_historical_cutoff,
-                    cursor,
-                })
-                .send()
-                .await?;
-            let body: GetTradeOffersResponse = parses_response(response).await?;
-            let next_cursor = body.response.next_cursor;
+            let body: GetTradeOffersResponse = self.call_webapi("IEconService", "GetTradeOffers", 1, &GetTradeOffersRequest {
+                language: &self.language,
+                active_only: *filter == OfferFilter::ActiveOnly,
+                historical_only: *filter == OfferFilter::HistoricalOnly,
+                get_sent_offers: true,
+                get_received_offers: true,
+                get_descriptions: self.get_descriptions,
+                time_historical_cutoff,
+                cursor,
+            }).await?;
+            let next_cursor = body.next_cursor;
             
-            responses.push(body.response);
+            responses.push(body);
             
             if next_cursor > Some(0) {
                 cursor = next_cursor;
@@ -481,25 +602,53 @@ impl SteamTradeOfferAPI {
         }
         
         let mut response_offers = Vec::new();
+        let mut descriptions = HashMap::new();
         
         for mut response in responses {
             response_offers.append(&mut response.trade_offers_received);
             response_offers.append(&mut response.trade_offers_sent);
+            descriptions.extend(response.descriptions);
         }
-
-        let classes = response_offers
+        
+        self.resolve_raw_trade_offers(response_offers, descriptions).await
+    }
+    
+    /// Gets an offer with its items.
+    pub async fn get_offer(
+        &self,
+        tradeofferid: TradeOfferId,
+    ) -> Result<response::trade_offer::TradeOffer, Error> {
+        let body: GetTradeOfferResponse = self.call_webapi("IEconService", "GetTradeOffer", 1, &GetTradeOfferRequest {
+            tradeofferid,
+            language: &self.language,
+            get_descriptions: self.get_descriptions,
+        }).await?;
+        let mut offers = self.resolve_raw_trade_offers(vec![body.offer], body.descriptions).await?;
+        
+        // there is always exactly one offer
+        Ok(offers.remove(0))
+    }
+    
+    /// Resolves the items in raw offers to assets using the descriptions from the response, 
+    /// requesting classinfos for any classes without descriptions.
+    async fn resolve_raw_trade_offers(
+        &self,
+        offers: Vec<raw::RawTradeOffer>,
+        descriptions: HashMap<ClassInfoClass, String>,
+    ) -> Result<Vec<response::trade_offer::TradeOffer>, Error> {
+        let classes = offers
             .iter()
             .flat_map(|offer| {
                 offer.items_to_give
                     .iter()
                     .chain(offer.items_to_receive.iter())
+                    // currencies are not assets
+                    .filter(|item| item.assetid.is_some())
                     .map(|item| (item.appid, item.classid, item.instanceid))
             })
-            .collect::<HashSet<_>>()
-            .into_iter()
-            .collect();
-        let map = self.get_asset_classinfos(&classes).await?;
-        let offers = response_offers
+            .collect::<HashSet<_>>();
+        let map = self.get_classinfos_with_descriptions(classes, descriptions).await?;
+        let offers = offers
             .into_iter()
             .map(|offer| from_raw_trade_offer(offer, &map))
             .collect::<Result<Vec<_>, _>>()?;
@@ -507,69 +656,200 @@ impl SteamTradeOfferAPI {
         Ok(offers)
     }
 
+    /// Gets classinfos for the given classes using the descriptions from a response, requesting
+    /// classinfos for any classes without descriptions.
+    async fn get_classinfos_with_descriptions(
+        &self,
+        classes: HashSet<ClassInfoClass>,
+        descriptions: HashMap<ClassInfoClass, String>,
+    ) -> Result<ClassInfoMap, Error> {
+        let mut map = if descriptions.is_empty() {
+            HashMap::new()
+        } else {
+            self.save_classinfos(descriptions).await?
+        };
+        let classes = classes
+            .into_iter()
+            .filter(|class| !map.contains_key(class))
+            .collect::<Vec<_>>();
+        
+        map.extend(self.get_asset_classinfos(&classes).await?);
+        
+        Ok(map)
+    }
+    
+    /// Gets the status of a trade along with the new IDs of its items.
+    pub async fn get_trade_status(
+        &self,
+        tradeid: TradeId,
+    ) -> Result<response::trade::Trade, Error> {
+        let body: GetTradeStatusResponse = self.call_webapi("IEconService", "GetTradeStatus", 1, &GetTradeStatusRequest {
+            tradeid,
+            language: &self.language,
+            get_descriptions: self.get_descriptions,
+        }).await?;
+        let trade = body.trades
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::Response("No trade in response".into()))?;
+        let classes = trade.assets_received
+            .iter()
+            .chain(trade.assets_given.iter())
+            .map(|item| (item.appid, item.classid, item.instanceid))
+            .collect::<HashSet<_>>();
+        let map = self.get_classinfos_with_descriptions(classes, body.descriptions).await?;
+        
+        Ok(from_raw_trade(trade, &map)?)
+    }
+    
+    /// Gets counts of offers. Offers are counted as new or updated if they changed after 
+    /// `time_last_visit`.
+    pub async fn get_trade_offers_summary(
+        &self,
+        time_last_visit: Option<ServerTime>,
+    ) -> Result<response::trade_offers_summary::TradeOffersSummary, Error> {
+        self.call_webapi("IEconService", "GetTradeOffersSummary", 1, &GetTradeOffersSummaryRequest {
+            time_last_visit: time_last_visit.map(|time| time.timestamp()),
+        }).await
+    }
+    
+    /// Gets a page of trade history.
+    pub async fn get_trade_history(
+        &self,
+        options: &request::trade_history::TradeHistoryOptions,
+    ) -> Result<response::trade_history::TradeHistory, Error> {
+        let body: GetTradeHistoryResponse = self.call_webapi("IEconService", "GetTradeHistory", 1, &GetTradeHistoryRequest {
+            max_trades: options.max_trades,
+            start_after_time: options.start_after_time.map(|time| time.timestamp()),
+            start_after_tradeid: options.start_after_tradeid,
+            navigating_back: options.navigating_back,
+            get_descriptions: self.get_descriptions,
+            language: &self.language,
+            include_failed: options.include_failed,
+            include_total: true,
+        }).await?;
+        let classes = body.trades
+            .iter()
+            .flat_map(|trade| {
+                trade.assets_received
+                    .iter()
+                    .chain(trade.assets_given.iter())
+                    .map(|item| (item.appid, item.classid, item.instanceid))
+            })
+            .collect::<HashSet<_>>();
+        let map = self.get_classinfos_with_descriptions(classes, body.descriptions).await?;
+        let trades = body.trades
+            .into_iter()
+            .map(|trade| from_raw_trade(trade, &map))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        Ok(response::trade_history::TradeHistory {
+            trades,
+            more: body.more,
+            total_trades: body.total_trades,
+        })
+    }
+    
+    /// Gets an offer without its items' classinfos.
     pub async fn get_trade_offer(
         &self,
         tradeofferid: TradeOfferId,
     ) -> Result<raw::RawTradeOffer, Error> {
-        #[derive(Serialize, Debug)]
-        struct Form<'a> {
-            key: &'a str,
-            tradeofferid: TradeOfferId,
-        }
-
-        #[derive(Deserialize, Debug)]
-        struct Body {
-            offer: raw::RawTradeOffer,
-        }
-
-        #[derive(Deserialize, Debug)]
-        struct Response {
-            response: Body,
-        }
-
-        let uri = self.get_api_url("IEconService", "GetTradeOffer", 1);
+        let body: GetTradeOfferResponse = self.call_webapi("IEconService", "GetTradeOffer", 1, &GetTradeOfferRequest {
+            tradeofferid,
+            language: &self.language,
+            get_descriptions: false,
+        }).await?;
+        
+        Ok(body.offer)
+    }
+    
+    /// Calls a Steam Web API method using GET. The API key is added to the parameters. Returns 
+    /// the contents of the `response` key in the response body.
+    pub async fn call_webapi<P, R>(
+        &self,
+        interface: &str,
+        method: &str,
+        version: usize,
+        params: &P,
+    ) -> Result<R, Error>
+    where
+        P: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let uri = self.get_api_url(interface, method, version);
         let response = self.client.get(&uri)
-            .query(&Form {
-                key: &self.key,
-                tradeofferid,
-            })
+            .query(&[("key", &self.key)])
+            .query(params)
             .send()
             .await?;
-        let body: Response = parses_response(response).await?;
+        let body: WebAPIResponse<R> = parses_response(response).await?;
         
-        Ok(body.response.offer)
+        Ok(body.response)
+    }
+    
+    /// Calls a Steam Web API method using POST. The API key is added to the parameters. Returns 
+    /// the contents of the `response` key in the response body.
+    pub async fn call_webapi_post<P, R>(
+        &self,
+        interface: &str,
+        method: &str,
+        version: usize,
+        params: &P,
+    ) -> Result<R, Error>
+    where
+        P: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let uri = self.get_api_url(interface, method, version);
+        let response = self.client.post(&uri)
+            .query(&[("key", &self.key)])
+            .form(params)
+            .send()
+            .await?;
+        let body: WebAPIResponse<R> = parses_response(response).await?;
+        
+        Ok(body.response)
     }
 
-    pub async fn get_user_details(
+    /// Gets the trade hold durations for trading with a partner using the Steam Web API. Unlike 
+    /// [`SteamTradeOfferAPI::get_user_details`] this does not require a session.
+    pub async fn get_trade_hold_durations(
         &self,
-        tradeofferid: &Option<TradeOfferId>,
         partner: &SteamID,
         token: &Option<String>,
     ) -> Result<response::user_details::UserDetails, Error> {
-        #[derive(Serialize, Debug)]
-        struct Params<'b> {
-            partner: u32,
-            token: &'b Option<String>,
-        }
-
-        fn get_days(group: Option<(&str, &str)>) -> u32 {
-            match group {
-                Some((_, days_str)) => {
-                    match days_str.parse::<u32>() {
-                        Ok(days) => days,
-                        Err(_e) => 0,
-                    }
-                },
-                None => 0,
-            }
+        fn get_days(seconds: u32) -> u32 {
+            seconds.div_ceil(ONE_DAY_SECS)
         }
         
+        let body: GetTradeHoldDurationsResponse = self.call_webapi("IEconService", "GetTradeHoldDurations", 1, &GetTradeHoldDurationsRequest {
+            steamid_target: partner,
+            trade_offer_access_token: token,
+        }).await?;
+        
+        Ok(response::user_details::UserDetails {
+            my_escrow: get_days(body.my_escrow.escrow_end_duration_seconds),
+            them_escrow: get_days(body.their_escrow.escrow_end_duration_seconds),
+            me: None,
+            them: None,
+        })
+    }
+    
+    /// Gets details for trading with a partner from the trade offer page, including escrow 
+    /// durations, persona names and the apps each side has items in.
+    pub async fn get_user_details(
+        &self,
+        tradeofferid: &Option<TradeOfferId>,
+        partner: &SteamID,
+        token: &Option<String>,
+    ) -> Result<response::user_details::UserDetails, Error> {
         let uri = {
             let pathname: String = match tradeofferid {
                 Some(id) => id.to_string(),
                 None => String::from("new"),
             };
-            let qs_params = serde_qs::to_string(&Params {
+            let qs_params = serde_qs::to_string(&TradeOfferPageQuery {
                 partner: partner.account_id(),
                 token,
             })?;
@@ -587,35 +867,59 @@ impl SteamTradeOfferAPI {
             .text()
             .await?;
         
-        if regex_is_match!(r#"/\n\W*<script type="text/javascript">\W*\r?\n?(\W*var g_rgAppContextData[\s\S]*)</script>"#, &body) {
-            let my_escrow = get_days(regex_captures!(r#"var g_daysMyEscrow = (\d+);"#, &body));
-            let them_escrow = get_days(regex_captures!(r#"var g_daysTheirEscrow = (\d+);"#, &body));
+        Ok(parse_user_details(&body)?)
+    }
 
-            Ok(response::user_details::UserDetails {
-                my_escrow,
-                them_escrow,
-            })
+    /// Gets our trade URL from the trade offer privacy page.
+    pub async fn get_trade_url(&self) -> Result<TradeUrl, Error> {
+        let uri = self.get_uri("/my/tradeoffers/privacy");
+        let response = self.client.get(&uri)
+            .send()
+            .await?;
+        let body = String::from_utf8_lossy(&check_response(response).await?).to_string();
+        
+        if let Some(trade_url) = parse_trade_url(&body) {
+            Ok(trade_url.parse::<TradeUrl>()?)
+        } else if regex_is_match!(r#"<h1>Sign In</h1>"#, &body) {
+            Err(Error::NotLoggedIn)
         } else {
-            Err(Error::Response("Malformed response".into()))
+            Err(Error::Response("Missing trade URL".into()))
         }
     }
-
+    
+    /// Generates a new token for our trade URL, invalidating the old one. Returns the new token.
+    pub async fn regenerate_trade_url_token(&self) -> Result<String, Error> {
+        let sessionid = self.sessionid.read().unwrap().clone();
+        
+        if sessionid.is_none() {
+            return Err(Error::NotLoggedIn);
+        }
+        
+        let referer = self.get_uri("/my/tradeoffers/privacy");
+        let uri = self.get_uri("/my/tradeoffers/newtradeurl");
+        let response = self.client.post(&uri)
+            .header(REFERER, referer)
+            .form(&NewTradeUrlRequest {
+                sessionid: &sessionid.unwrap(),
+            })
+            .send()
+            .await?;
+        // the response is the new token as a JSON string
+        let token: String = parses_response(response).await?;
+        
+        if !TradeUrl::is_valid_token(&token) {
+            return Err(Error::Response(format!("Invalid token: {}", token)));
+        }
+        
+        Ok(token)
+    }
+    
+    /// Accepts an offer.
     pub async fn accept_offer(
         &self,
         tradeofferid: TradeOfferId,
         partner: &SteamID,
     ) -> Result<response::accepted_offer::AcceptedOffer, Error> {
-        #[derive(Serialize, Debug)]
-        struct AcceptOfferParams<'a, 'b> {
-            sessionid: &'a String,
-            serverid: u32,
-            #[serde(with = "string")]
-            tradeofferid: TradeOfferId,
-            captcha: &'static str,
-            #[serde(serialize_with = "steamid_as_string")]
-            partner: &'b SteamID,
-        }
-        
         let sessionid = self.sessionid.read().unwrap().clone();
         
         if sessionid.is_none() {
@@ -623,7 +927,7 @@ impl SteamTradeOfferAPI {
         }
         
         let referer = self.get_uri(&format!("/tradeoffer/{}", tradeofferid));
-        let params = AcceptOfferParams {
+        let params = AcceptOfferRequest {
             sessionid: &sessionid.unwrap(),
             tradeofferid,
             partner,
@@ -641,21 +945,11 @@ impl SteamTradeOfferAPI {
         Ok(body)
     }
 
+    /// Declines an offer we received.
     pub async fn decline_offer(
         &self,
         tradeofferid: TradeOfferId,
     ) -> Result<TradeOfferId, Error> {
-        #[derive(Serialize, Debug)]
-        struct DeclineOfferParams<'a> {
-            sessionid: &'a String,
-        }
-        
-        #[derive(Deserialize, Debug)]
-        struct Response {
-            #[serde(with = "string")]
-            tradeofferid: TradeOfferId,
-        }
-        
         let sessionid = self.sessionid.read().unwrap().clone();
         
         if sessionid.is_none() {
@@ -666,31 +960,21 @@ impl SteamTradeOfferAPI {
         let uri = self.get_uri(&format!("/tradeoffer/{}/decline", tradeofferid));
         let response = self.client.post(&uri)
             .header(REFERER, referer)
-            .form(&DeclineOfferParams {
+            .form(&DeclineOfferRequest {
                 sessionid: &sessionid.unwrap(),
             })
             .send()
             .await?;
-        let body: Response = parses_response(response).await?;
+        let body: DeclineOfferResponse = parses_response(response).await?;
         
         Ok(body.tradeofferid)
     }
     
+    /// Cancels an offer we sent.
     pub async fn cancel_offer(
         &self,
         tradeofferid: TradeOfferId,
     ) -> Result<TradeOfferId, Error> {
-        #[derive(Serialize, Debug)]
-        struct CancelOfferParams<'a> {
-            sessionid: &'a String,
-        }
-        
-        #[derive(Deserialize, Debug)]
-        struct Response {
-            #[serde(with = "string")]
-            tradeofferid: TradeOfferId,
-        }
-        
         let sessionid = self.sessionid.read().unwrap().clone();
         
         if sessionid.is_none() {
@@ -701,16 +985,17 @@ impl SteamTradeOfferAPI {
         let uri = self.get_uri(&format!("/tradeoffer/{}/cancel", tradeofferid));
         let response = self.client.post(&uri)
             .header(REFERER, referer)
-            .form(&CancelOfferParams {
+            .form(&CancelOfferRequest {
                 sessionid: &sessionid.unwrap(),
             })
             .send()
             .await?;
-        let body: Response = parses_response(response).await?;
+        let body: CancelOfferResponse = parses_response(response).await?;
         
         Ok(body.tradeofferid)
     }
     
+    /// Gets an inventory using the older inventory endpoint.
     pub async fn get_inventory_old(
         &self,
         steamid: &SteamID,
@@ -718,13 +1003,6 @@ impl SteamTradeOfferAPI {
         contextid: ContextId,
         tradable_only: bool,
     ) -> Result<Vec<response::asset::Asset>, Error> { 
-        #[derive(Serialize, Debug)]
-        struct Query<'a> {
-            l: &'a str,
-            start: Option<u64>,
-            trading: bool,
-        }
-        
         let mut responses: Vec<GetInventoryOldResponse> = Vec::new();
         let mut start: Option<u64> = None;
         let sid = u64::from(*steamid);
@@ -734,7 +1012,7 @@ impl SteamTradeOfferAPI {
         loop {
             let response = self.client.get(&uri)
                 .header(REFERER, &referer)
-                .query(&Query {
+                .query(&GetInventoryOldRequest {
                     l: &self.language,
                     trading: tradable_only,
                     start,
@@ -759,31 +1037,73 @@ impl SteamTradeOfferAPI {
             }
         }
         
-        let mut inventory: Inventory = Vec::new();
+        Ok(from_old_inventory_responses(responses, appid, contextid)?)
+    }
+    
+    /// Gets a trade partner's inventory from the trade offer page. This requires a session, and
+    /// works for partners whose inventory is only visible to trade partners.
+    pub async fn get_partner_inventory(
+        &self,
+        partner: &SteamID,
+        appid: AppId,
+        contextid: ContextId,
+        token: &Option<String>,
+    ) -> Result<Vec<response::asset::Asset>, Error> {
+        let sessionid = self.sessionid.read().unwrap().clone();
         
-        for body in responses {
-            for item in body.assets.values() {
-                if let Some(classinfo) = body.descriptions.get(&(item.classid, item.instanceid)) {
-                    inventory.push(response::asset::Asset {
-                        classinfo: Arc::clone(classinfo),
-                        appid,
-                        contextid,
-                        assetid: item.assetid,
-                        amount: item.amount,
-                    });
-                } else {
-                    let instanceid =  item.instanceid.unwrap_or(0);
-                    
-                    return Err(Error::Response(
-                        format!("Missing descriptions for item {}:{}", item.classid, instanceid)
-                    ));
+        if sessionid.is_none() {
+            return Err(Error::NotLoggedIn);
+        }
+        
+        // presence of sessionid was checked above - unwrap is safe here
+        let sessionid = sessionid.unwrap();
+        let mut responses: Vec<GetInventoryOldResponse> = Vec::new();
+        let mut start: Option<u64> = None;
+        let uri = self.get_uri("/tradeoffer/new/partnerinventory/");
+        let referer = {
+            let qs_params = serde_qs::to_string(&TradeOfferPageQuery {
+                partner: partner.account_id(),
+                token,
+            })?;
+            
+            self.get_uri(&format!("/tradeoffer/new/?{}", qs_params))
+        };
+        
+        loop {
+            let response = self.client.get(&uri)
+                .header(REFERER, &referer)
+                .query(&GetPartnerInventoryRequest {
+                    sessionid: &sessionid,
+                    partner,
+                    appid,
+                    contextid,
+                    l: &self.language,
+                    start,
+                })
+                .send()
+                .await?;
+            let body: GetInventoryOldResponse = parses_response(response).await?;
+            
+            if !body.success {
+                return Err(Error::Response("Bad response".into()));
+            } else if body.more_items {
+                // shouldn't occur, but we wouldn't want to call this endlessly if it does...
+                if body.more_start == start {
+                    return Err(Error::Response("Bad response".into()));
                 }
+                
+                start = body.more_start;
+                responses.push(body);
+            } else {
+                responses.push(body);
+                break;
             }
         }
         
-        Ok(inventory)
+        Ok(from_old_inventory_responses(responses, appid, contextid)?)
     }
     
+    /// Gets an inventory.
     pub async fn get_inventory(
         &self,
         steamid: &SteamID,
@@ -791,13 +1111,6 @@ impl SteamTradeOfferAPI {
         contextid: ContextId,
         tradable_only: bool,
     ) -> Result<Vec<response::asset::Asset>, Error> { 
-        #[derive(Serialize, Debug)]
-        struct Query<'a> {
-            l: &'a str,
-            count: u32,
-            start_assetid: Option<u64>,
-        }
-        
         let mut responses: Vec<GetInventoryResponse> = Vec::new();
         let mut start_assetid: Option<u64> = None;
         let sid = u64::from(*steamid);
@@ -807,7 +1120,7 @@ impl SteamTradeOfferAPI {
         loop {
             let response = self.client.get(&uri)
                 .header(REFERER, &referer)
-                .query(&Query {
+                .query(&GetInventoryRequest {
                     l: &self.language,
                     count: 5000,
                     start_assetid,
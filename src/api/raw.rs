@@ -1,8 +1,8 @@
 use serde::{Serialize, Deserialize};
-use chrono::serde::ts_seconds;
+use chrono::serde::{ts_seconds, ts_seconds_option};
 use crate::{
     ServerTime,
-    enums::{ConfirmationMethod, TradeOfferState},
+    enums::{ConfirmationMethod, TradeOfferState, TradeStatus},
     serializers::{
         string,
         option_string,
@@ -12,6 +12,7 @@ use crate::{
         AppId,
         ContextId,
         AssetId,
+        CurrencyId,
         Amount,
         ClassId,
         InstanceId,
@@ -20,15 +21,21 @@ use crate::{
     }
 };
 
+/// An offer as returned from the Steam Web API, without classinfos for its items.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct RawTradeOfferNoItems {
+pub struct RawTradeOffer {
     #[serde(with = "string")]
     pub tradeofferid: TradeOfferId,
+    #[serde(default)]
     #[serde(with = "option_string")]
     pub tradeid: Option<TradeId>,
     pub accountid_other: u32,
     pub message: Option<String>,
     #[serde(default)]
+    pub items_to_receive: Vec<RawOfferItem>,
+    #[serde(default)]
+    pub items_to_give: Vec<RawOfferItem>,
+    #[serde(default)]
     pub is_our_offer: bool,
     #[serde(default)]
     pub from_real_time_trade: bool,
@@ -45,36 +52,29 @@ pub struct RawTradeOfferNoItems {
     pub confirmation_method: ConfirmationMethod,
 }
 
+/// An item in an offer as returned from the Steam Web API. Wallet currencies have a 
+/// `currencyid` in place of an `assetid`.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct RawTradeOffer {
+pub struct RawOfferItem {
+    pub appid: AppId,
     #[serde(with = "string")]
-    pub tradeofferid: TradeOfferId,
+    pub contextid: ContextId,
     #[serde(default)]
     #[serde(with = "option_string")]
-    pub tradeid: Option<TradeId>,
-    pub accountid_other: u32,
-    pub message: Option<String>,
-    #[serde(default)]
-    pub items_to_receive: Vec<RawAsset>,
+    pub assetid: Option<AssetId>,
     #[serde(default)]
-    pub items_to_give: Vec<RawAsset>,
-    #[serde(default)]
-    pub is_our_offer: bool,
+    #[serde(with = "option_string")]
+    pub currencyid: Option<CurrencyId>,
+    #[serde(with = "string")]
+    pub classid: ClassId,
     #[serde(default)]
-    pub from_real_time_trade: bool,
-    #[serde(with = "ts_seconds")]
-    pub expiration_time: ServerTime,
-    #[serde(with = "ts_seconds")]
-    pub time_created: ServerTime,
-    #[serde(with = "ts_seconds")]
-    pub time_updated: ServerTime,
-    pub trade_offer_state: TradeOfferState,
-    // todo parse 0 responses as null
-    #[serde(with = "ts_seconds")]
-    pub escrow_end_date: ServerTime,
-    pub confirmation_method: ConfirmationMethod,
+    #[serde(with = "option_string_0_as_none")]
+    pub instanceid: InstanceId,
+    #[serde(with = "string")]
+    pub amount: Amount,
 }
 
+/// An item from an inventory as returned from the Steam Web API.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RawAsset {
     pub appid: AppId,
@@ -90,6 +90,7 @@ pub struct RawAsset {
     pub amount: Amount,
 }
 
+/// An item from a trade receipt.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RawReceiptAsset {
     pub appid: AppId,
@@ -104,6 +105,7 @@ pub struct RawReceiptAsset {
     pub amount: Amount,
 }
 
+/// An item from the older inventory endpoint.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RawAssetOld {
     #[serde(with = "string", rename = "id")]
@@ -114,4 +116,46 @@ pub struct RawAssetOld {
     pub instanceid: InstanceId,
     #[serde(with = "string")]
     pub amount: Amount,
+}
+
+/// A trade as returned from the Steam Web API, without classinfos for its items.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RawTrade {
+    #[serde(with = "string")]
+    pub tradeid: TradeId,
+    #[serde(with = "string")]
+    pub steamid_other: u64,
+    #[serde(with = "ts_seconds")]
+    pub time_init: ServerTime,
+    #[serde(default)]
+    #[serde(with = "ts_seconds_option")]
+    pub time_escrow_end: Option<ServerTime>,
+    pub status: TradeStatus,
+    #[serde(default)]
+    pub assets_received: Vec<RawTradeAsset>,
+    #[serde(default)]
+    pub assets_given: Vec<RawTradeAsset>,
+}
+
+/// An item in a trade as returned from the Steam Web API.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RawTradeAsset {
+    pub appid: AppId,
+    #[serde(with = "string")]
+    pub contextid: ContextId,
+    #[serde(with = "string")]
+    pub assetid: AssetId,
+    #[serde(with = "string")]
+    pub amount: Amount,
+    #[serde(with = "string")]
+    pub classid: ClassId,
+    #[serde(default)]
+    #[serde(with = "option_string_0_as_none")]
+    pub instanceid: InstanceId,
+    #[serde(default)]
+    #[serde(with = "option_string")]
+    pub new_assetid: Option<AssetId>,
+    #[serde(default)]
+    #[serde(with = "option_string")]
+    pub new_contextid: Option<ContextId>,
 }
\ No newline at end of file
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+use crate::response::ClassInfo;
+
+/// Which other languages a field of a cached [`ClassInfo`] can be used from.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum FallbackLanguages {
+    /// Only the requested language is used.
+    #[default]
+    Strict,
+    /// Any language is used.
+    Any,
+    /// The given languages are used, in order of preference.
+    Languages(Vec<String>),
+}
+
+impl FallbackLanguages {
+    /// Whether fields in the given language can be used.
+    pub fn allows(&self, language: &str) -> bool {
+        match self {
+            Self::Strict => false,
+            Self::Any => true,
+            Self::Languages(languages) => languages.iter().any(|allowed| allowed == language),
+        }
+    }
+}
+
+/// Decides whether a cached [`ClassInfo`] in a different language can be used when one in the
+/// requested language is not cached. Names and tags have separate policies.
+///
+/// Names, descriptions and the display names of tags are localized, while fields such as
+/// `market_hash_name`, `app_data` and the `internal_name` and `category` of tags are the same in
+/// every language. A classinfo in another language is used if either policy allows that
+/// language. Fields whose policy does not allow it are cleared - see [`LanguageFallback::apply`].
+/// By default only classinfos in the requested language are used.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LanguageFallback {
+    /// The languages `name`, `market_name`, `type` and `descriptions` can be used from.
+    pub names: FallbackLanguages,
+    /// The languages the display names of tags can be used from.
+    pub tags: FallbackLanguages,
+}
+
+impl LanguageFallback {
+    /// Only classinfos in the requested language are used.
+    pub fn strict() -> Self {
+        Self::default()
+    }
+    
+    /// Classinfos in any language are used.
+    pub fn any() -> Self {
+        Self {
+            names: FallbackLanguages::Any,
+            tags: FallbackLanguages::Any,
+        }
+    }
+    
+    /// Classinfos in the given languages are used, in order of preference.
+    pub fn languages(languages: Vec<String>) -> Self {
+        Self {
+            names: FallbackLanguages::Languages(languages.clone()),
+            tags: FallbackLanguages::Languages(languages),
+        }
+    }
+    
+    /// Sets the languages names can be used from.
+    pub fn names(mut self, names: FallbackLanguages) -> Self {
+        self.names = names;
+        self
+    }
+    
+    /// Sets the languages the display names of tags can be used from.
+    pub fn tags(mut self, tags: FallbackLanguages) -> Self {
+        self.tags = tags;
+        self
+    }
+    
+    /// Whether only classinfos in the requested language are used.
+    pub fn is_strict(&self) -> bool {
+        self.names == FallbackLanguages::Strict && self.tags == FallbackLanguages::Strict
+    }
+    
+    /// Whether either policy allows any language.
+    pub fn allows_any(&self) -> bool {
+        self.names == FallbackLanguages::Any || self.tags == FallbackLanguages::Any
+    }
+    
+    /// Gets the languages to check after the requested language, given the languages known to
+    /// be cached. Languages allowed for names come first.
+    pub fn fallback_languages(
+        &self,
+        language: &str,
+        cached_languages: &[String],
+    ) -> Vec<String> {
+        let mut fallback_languages: Vec<String> = Vec::new();
+        
+        for policy in [&self.names, &self.tags] {
+            let languages = match policy {
+                FallbackLanguages::Strict => continue,
+                FallbackLanguages::Any => cached_languages,
+                FallbackLanguages::Languages(languages) => languages,
+            };
+            
+            for fallback_language in languages {
+                if fallback_language != language && !fallback_languages.contains(fallback_language) {
+                    fallback_languages.push(fallback_language.clone());
+                }
+            }
+        }
+        
+        fallback_languages
+    }
+    
+    /// Clears the fields of a classinfo in a fallback language that are not allowed in that
+    /// language. Without names, `name` is removed, `market_name` is replaced with the
+    /// language-independent `market_hash_name`, and `type` and `descriptions` are emptied.
+    /// Without tags, the display names of tags are replaced with their internal names.
+    pub fn apply(&self, language: &str, classinfo: Arc<ClassInfo>) -> Arc<ClassInfo> {
+        let allows_names = self.names.allows(language);
+        let allows_tags = self.tags.allows(language);
+        
+        if allows_names && allows_tags {
+            return classinfo;
+        }
+        
+        let mut classinfo = (*classinfo).clone();
+        
+        if !allows_names {
+            classinfo.name = None;
+            classinfo.market_name = classinfo.market_hash_name.clone();
+            classinfo.r#type = String::new();
+            classinfo.descriptions = Vec::new();
+        }
+        
+        if !allows_tags {
+            for tag in classinfo.tags.iter_mut() {
+                tag.name = tag.internal_name.clone();
+                tag.category_name = None;
+            }
+        }
+        
+        Arc::new(classinfo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn gets_fallback_languages() {
+        let cached_languages = vec!["english".to_string(), "german".to_string()];
+        
+        assert!(LanguageFallback::strict().fallback_languages("english", &cached_languages).is_empty());
+        assert_eq!(
+            LanguageFallback::any().fallback_languages("english", &cached_languages),
+            vec!["german".to_string()],
+        );
+        assert_eq!(
+            LanguageFallback::languages(vec!["french".into(), "english".into()])
+                .fallback_languages("english", &cached_languages),
+            vec!["french".to_string()],
+        );
+        assert_eq!(
+            LanguageFallback::strict()
+                .names(FallbackLanguages::Languages(vec!["french".into()]))
+                .tags(FallbackLanguages::Any)
+                .fallback_languages("english", &cached_languages),
+            vec!["french".to_string(), "german".to_string()],
+        );
+    }
+    
+    #[test]
+    fn clears_fields_that_are_not_allowed() {
+        let classinfo: Arc<ClassInfo> = Arc::new(serde_json::from_str(include_str!("../response/fixtures/classinfo_refined_metal.json")).unwrap());
+        let fallback = LanguageFallback::strict().tags(FallbackLanguages::Any);
+        let applied = fallback.apply("german", Arc::clone(&classinfo));
+        
+        assert_eq!(applied.name, None);
+        assert_eq!(applied.market_name, classinfo.market_hash_name);
+        assert!(applied.descriptions.is_empty());
+        assert_eq!(applied.tags, classinfo.tags);
+        
+        let applied = LanguageFallback::any().apply("german", Arc::clone(&classinfo));
+        
+        assert!(Arc::ptr_eq(&applied, &classinfo));
+    }
+}
@@ -1,20 +1,32 @@
 mod types;
-pub mod helpers;
+mod store;
+mod language_fallback;
+
+pub use types::ClassInfoFile;
+pub use store::{
+    ClassInfoStore,
+    NoDiskClassInfoStore,
+    DirectoryClassInfoStore,
+    FileClassInfoStore,
+};
+pub use language_fallback::{LanguageFallback, FallbackLanguages};
 
 use crate::{
     response::classinfo::ClassInfo,
     types::ClassInfoClass,
 };
-use std::{sync::Arc, collections::HashMap};
+use std::{sync::Arc, collections::{HashMap, HashSet}};
 use lfu_cache::LfuCache;
 
-type LfuClassInfoMap = LfuCache<ClassInfoClass, Arc<ClassInfo>>;
+type LfuClassInfoMap = LfuCache<(String, ClassInfoClass), Arc<ClassInfo>>;
 
 /// Used for storing caches for [`ClassInfo`] data. Data is stored using an [`LfuCache`]
-/// to limit how many elements are stored in memory.
+/// to limit how many elements are stored in memory. Classinfos are keyed by language as well as
+/// by class since names and descriptions are localized.
 #[derive(Debug)]
 pub struct ClassInfoCache {
     map: LfuClassInfoMap,
+    languages: HashSet<String>,
 }
 
 fn create_map(capacity: usize) -> LfuClassInfoMap {
@@ -23,9 +35,7 @@ fn create_map(capacity: usize) -> LfuClassInfoMap {
 
 impl Default for ClassInfoCache {
     fn default() -> Self {
-        Self {
-            map: create_map(500),
-        }
+        Self::new(500)
     }
 }
 
@@ -33,33 +43,46 @@ impl ClassInfoCache {
     pub fn new(capacity: usize) -> Self {
         Self {
             map: create_map(capacity),
+            languages: HashSet::new(),
         }
     }
     
-    /// Gets a [`ClassInfo`] wrapped in an [`Arc`] from the cache.
+    /// Gets a [`ClassInfo`] in the given language wrapped in an [`Arc`] from the cache.
     pub fn get_classinfo(
         &mut self,
+        language: &str,
         class: &ClassInfoClass,
     ) -> Option<Arc<ClassInfo>> {
-        self.map.get(class).map(Arc::clone)
+        self.map.get(&(language.to_string(), *class)).map(Arc::clone)
     }
     
-    /// Inserts a [`ClassInfo`] into the cache.
+    /// Inserts a [`ClassInfo`] in the given language into the cache.
     pub fn insert(
         &mut self,
+        language: &str,
         class: ClassInfoClass,
         classinfo: Arc<ClassInfo>,
     ) {
-        self.map.insert(class, classinfo);
+        if !self.languages.contains(language) {
+            self.languages.insert(language.to_string());
+        }
+        
+        self.map.insert((language.to_string(), class), classinfo);
     }
     
-    /// Inserts a set of [`ClassInfo`] elements into the cache.
+    /// Inserts a set of [`ClassInfo`] elements in the given language into the cache.
     pub fn insert_classinfos(
         &mut self,
+        language: &str,
         classinfos: &HashMap<ClassInfoClass, Arc<ClassInfo>>,
     ) {
         for (class, classinfo) in classinfos {
-            self.map.insert(*class, Arc::clone(classinfo));
+            self.insert(language, *class, Arc::clone(classinfo));
         }
     }
+    
+    /// The languages of classinfos that have been inserted into the cache.
+    pub fn languages(&self) -> Vec<String> {
+        self.languages.iter().cloned().collect()
+    }
 }
\ No newline at end of file
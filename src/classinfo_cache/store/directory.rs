@@ -0,0 +1,194 @@
+use super::ClassInfoStore;
+use crate::{
+    response::classinfo::ClassInfo,
+    error::FileError,
+    helpers::write_file_atomic,
+    classinfo_cache::types::ClassInfoFile,
+    types::ClassInfoClass,
+};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use std::{fs, path::PathBuf, collections::HashMap};
+
+const DEFAULT_CONCURRENCY: usize = 10;
+
+/// Checks that a language is a plain name such as `"english"` so it can't point outside of the
+/// store's directory.
+fn is_valid_language(language: &str) -> bool {
+    !language.is_empty() && language.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+}
+
+/// Stores each classinfo as a JSON file in a directory, with subfolders for each language and
+/// app e.g. `<directory>/english/440/2674_11040547.json`. File access is done on blocking
+/// threads.
+#[derive(Debug, Clone)]
+pub struct DirectoryClassInfoStore {
+    directory: PathBuf,
+    concurrency: usize,
+}
+
+impl DirectoryClassInfoStore {
+    /// Creates a new store in the given directory. Directories are created as needed.
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        Self {
+            directory: directory.into(),
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+    
+    /// The maximum number of files read or written at once. Defaults to 10.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+    
+    fn get_filepath(&self, language: &str, class: &ClassInfoClass) -> Result<PathBuf, FileError> {
+        if !is_valid_language(language) {
+            return Err(FileError::InvalidLanguage(language.into()));
+        }
+        
+        let (appid, classid, instanceid) = class;
+        
+        Ok(self.directory
+            .join(language)
+            .join(appid.to_string())
+            .join(format!("{}_{}.json", classid, instanceid.unwrap_or(0))))
+    }
+    
+    async fn load_classinfo(
+        &self,
+        language: &str,
+        class: ClassInfoClass,
+    ) -> Result<ClassInfoFile, FileError> {
+        let filepath = self.get_filepath(language, &class)?;
+        
+        spawn_blocking(move || {
+            let data = fs::read_to_string(filepath)?;
+            let classinfo = serde_json::from_str::<ClassInfo>(&data)?;
+            
+            Ok((class, classinfo))
+        }).await
+    }
+    
+    async fn save_classinfo(
+        &self,
+        language: &str,
+        class: &ClassInfoClass,
+        classinfo: &str,
+    ) -> Result<(), FileError> {
+        let filepath = self.get_filepath(language, class)?;
+        let classinfo = classinfo.to_string();
+        
+        spawn_blocking(move || {
+            if let Some(parent) = filepath.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            
+            write_file_atomic(&filepath, classinfo.as_bytes())
+        }).await
+    }
+}
+
+/// Runs `f` on a blocking thread.
+async fn spawn_blocking<T, F>(f: F) -> Result<T, FileError>
+where
+    F: FnOnce() -> Result<T, FileError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|_| FileError::JoinError)?
+}
+
+#[async_trait]
+impl ClassInfoStore for DirectoryClassInfoStore {
+    async fn load_classinfos(
+        &self,
+        language: &str,
+        classes: &[ClassInfoClass],
+    ) -> Vec<ClassInfoFile> {
+        let tasks = classes
+            .iter()
+            .map(|class| self.load_classinfo(language, *class))
+            .collect::<Vec<_>>();
+        let results = stream::iter(tasks)
+            .buffer_unordered(self.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+        
+        results
+            .into_iter()
+            // files that are missing or can't be parsed are skipped
+            .filter_map(|result| result.ok())
+            .collect()
+    }
+    
+    async fn save_classinfos(
+        &self,
+        language: &str,
+        classinfos: &HashMap<ClassInfoClass, String>,
+    ) -> Result<(), FileError> {
+        let tasks = classinfos
+            .iter()
+            .map(|(class, classinfo)| self.save_classinfo(language, class, classinfo))
+            .collect::<Vec<_>>();
+        let results = stream::iter(tasks)
+            .buffer_unordered(self.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+        
+        results.into_iter().collect()
+    }
+    
+    async fn languages(&self) -> Vec<String> {
+        let directory = self.directory.clone();
+        let result = spawn_blocking(move || {
+            let languages = fs::read_dir(directory)?
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+                .filter_map(|entry| entry.file_name().to_str().map(|language| language.to_string()))
+                .filter(|language| is_valid_language(language))
+                .collect();
+            
+            Ok(languages)
+        }).await;
+        
+        result.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn saves_and_loads_classinfos() {
+        let directory = std::env::temp_dir()
+            .join(format!("steam_tradeoffers_test_{}", std::process::id()))
+            .join("classinfos");
+        let store = DirectoryClassInfoStore::new(&directory);
+        let class = (440, 2674, Some(11040547));
+        let classinfo = include_str!("../../response/fixtures/classinfo_refined_metal.json");
+        let classinfos = HashMap::from([(class, classinfo.to_string())]);
+        
+        store.save_classinfos("english", &classinfos).await.unwrap();
+        
+        assert!(directory.join("english").join("440").join("2674_11040547.json").exists());
+        
+        let loaded = store.load_classinfos("english", &[class, (440, 1, None)]).await;
+        
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].0, class);
+        assert!(store.load_classinfos("german", &[class]).await.is_empty());
+        assert!(matches!(
+            store.save_classinfos("../english", &classinfos).await,
+            Err(FileError::InvalidLanguage(_)),
+        ));
+        
+        std::fs::write(directory.join("notes.txt"), "").unwrap();
+        
+        assert_eq!(store.languages().await, vec!["english".to_string()]);
+        
+        let _ = std::fs::remove_dir_all(directory);
+    }
+}
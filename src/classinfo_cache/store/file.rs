@@ -0,0 +1,315 @@
+use super::ClassInfoStore;
+use crate::{
+    response::classinfo::ClassInfo,
+    error::FileError,
+    classinfo_cache::types::ClassInfoFile,
+    types::ClassInfoClass,
+};
+use async_trait::async_trait;
+use std::{
+    fs,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    collections::{HashMap, HashSet},
+};
+
+/// The byte offset and length of a classinfo within the file.
+type IndexEntry = (u64, usize);
+
+#[derive(Debug)]
+struct FileIndex {
+    file: fs::File,
+    index: HashMap<(String, ClassInfoClass), IndexEntry>,
+}
+
+/// Stores all classinfos in a single file. Each line in the file holds one classinfo in the
+/// format `<language>\t<appid>\t<classid>\t<instanceid>\t<json>`. An index of where each classinfo is located
+/// in the file is built when the file is first accessed so that only the requested classinfos
+/// are read. New classinfos are appended to the end of the file. File access is done on
+/// blocking threads.
+#[derive(Debug, Clone)]
+pub struct FileClassInfoStore {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    filepath: PathBuf,
+    state: Mutex<Option<FileIndex>>,
+}
+
+impl Inner {
+    /// Opens the file and builds the index.
+    fn open(&self) -> Result<FileIndex, FileError> {
+        fn parse_key(line: &str) -> Option<((String, ClassInfoClass), usize)> {
+            let mut split = line.splitn(5, '\t');
+            let language = split.next()?.to_string();
+            let appid = split.next()?.parse::<u32>().ok()?;
+            let classid = split.next()?.parse::<u64>().ok()?;
+            let instanceid = match split.next()?.parse::<u64>().ok()? {
+                0 => None,
+                instanceid => Some(instanceid),
+            };
+            let json = split.next()?;
+            
+            Some(((language, (appid, classid, instanceid)), line.len() - json.len()))
+        }
+        
+        if let Some(parent) = self.filepath.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.filepath)?;
+        let mut index = HashMap::new();
+        let mut offset: u64 = 0;
+        
+        {
+            let mut reader = BufReader::new(&mut file);
+            let mut line = String::new();
+            
+            loop {
+                line.clear();
+                
+                let read = reader.read_line(&mut line)?;
+                
+                // an incomplete line is left behind if a write was interrupted
+                if read == 0 || !line.ends_with('\n') {
+                    break;
+                }
+                
+                let trimmed = line.trim_end_matches('\n');
+                
+                if let Some((key, json_start)) = parse_key(trimmed) {
+                    let json_len = trimmed.len() - json_start;
+                    
+                    index.insert(key, (offset + json_start as u64, json_len));
+                }
+                
+                offset += read as u64;
+            }
+        }
+        
+        // discard anything after the last complete line
+        file.set_len(offset)?;
+        
+        Ok(FileIndex {
+            file,
+            index,
+        })
+    }
+    
+    /// Calls `f` with the index, opening the file first if needed. The lock on the index is 
+    /// held while `f` runs.
+    fn with_index<T, F>(&self, f: F) -> Result<T, FileError>
+    where
+        F: FnOnce(&mut FileIndex) -> Result<T, FileError>,
+    {
+        let mut state = self.state.lock().unwrap();
+        
+        if state.is_none() {
+            *state = Some(self.open()?);
+        }
+        
+        // presence was checked above
+        f(state.as_mut().unwrap())
+    }
+    
+    fn load_classinfos(
+        &self,
+        language: String,
+        classes: Vec<ClassInfoClass>,
+    ) -> Result<Vec<ClassInfoFile>, FileError> {
+        let entries = self.with_index(|file_index| {
+            let entries = classes
+                .into_iter()
+                .filter_map(|class| {
+                    file_index.index
+                        .get(&(language.clone(), class))
+                        .map(|entry| (class, *entry))
+                })
+                .collect::<Vec<_>>();
+            
+            Ok(entries)
+        })?;
+        
+        if entries.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        // reads use their own handle so the index is not locked while reading
+        let mut file = fs::File::open(&self.filepath)?;
+        let mut classinfos = Vec::new();
+        
+        for (class, (offset, len)) in entries {
+            let mut buffer = vec![0; len];
+            
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut buffer)?;
+            
+            if let Ok(classinfo) = serde_json::from_slice::<ClassInfo>(&buffer) {
+                classinfos.push((class, classinfo));
+            }
+        }
+        
+        Ok(classinfos)
+    }
+    
+    fn save_classinfos(
+        &self,
+        language: String,
+        classinfos: HashMap<ClassInfoClass, String>,
+    ) -> Result<(), FileError> {
+        // these would break the format of the line
+        if language.contains(['\t', '\n']) {
+            return Err(FileError::InvalidLanguage(language));
+        }
+        
+        let mut lines = Vec::with_capacity(classinfos.len());
+        
+        for ((appid, classid, instanceid), classinfo) in classinfos {
+            // re-serialized so the classinfo is guaranteed to be on one line
+            let json = serde_json::from_str::<serde_json::Value>(&classinfo)?.to_string();
+            let key = format!("{}\t{}\t{}\t{}\t", language, appid, classid, instanceid.unwrap_or(0));
+            
+            lines.push(((appid, classid, instanceid), key, json));
+        }
+        
+        self.with_index(|file_index| {
+            let previous_len = file_index.file.seek(SeekFrom::End(0))?;
+            let mut offset = previous_len;
+            let mut data = String::new();
+            let mut entries = Vec::with_capacity(lines.len());
+            
+            for (class, key, json) in lines {
+                entries.push(((language.clone(), class), (offset + key.len() as u64, json.len())));
+                offset += (key.len() + json.len() + 1) as u64;
+                data.push_str(&key);
+                data.push_str(&json);
+                data.push('\n');
+            }
+            
+            let written = file_index.file.write_all(data.as_bytes())
+                .and_then(|_| file_index.file.flush());
+            
+            if let Err(error) = written {
+                // remove any partial line so the next append starts on a new line
+                let _ = file_index.file.set_len(previous_len);
+                
+                return Err(error.into());
+            }
+            
+            file_index.index.extend(entries);
+            
+            Ok(())
+        })
+    }
+}
+
+impl FileClassInfoStore {
+    /// Creates a new store at the given file path. Parent directories are created as needed.
+    pub fn new<P: Into<PathBuf>>(filepath: P) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                filepath: filepath.into(),
+                state: Mutex::new(None),
+            }),
+        }
+    }
+    
+    /// Runs `f` on a blocking thread.
+    async fn spawn_blocking<T, F>(&self, f: F) -> Result<T, FileError>
+    where
+        F: FnOnce(&Inner) -> Result<T, FileError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+        
+        tokio::task::spawn_blocking(move || f(&inner))
+            .await
+            .map_err(|_| FileError::JoinError)?
+    }
+}
+
+#[async_trait]
+impl ClassInfoStore for FileClassInfoStore {
+    async fn load_classinfos(
+        &self,
+        language: &str,
+        classes: &[ClassInfoClass],
+    ) -> Vec<ClassInfoFile> {
+        let language = language.to_string();
+        let classes = classes.to_vec();
+        
+        self.spawn_blocking(move |inner| inner.load_classinfos(language, classes))
+            .await
+            .unwrap_or_default()
+    }
+    
+    async fn save_classinfos(
+        &self,
+        language: &str,
+        classinfos: &HashMap<ClassInfoClass, String>,
+    ) -> Result<(), FileError> {
+        let language = language.to_string();
+        let classinfos = classinfos.clone();
+        
+        self.spawn_blocking(move |inner| inner.save_classinfos(language, classinfos)).await
+    }
+    
+    async fn languages(&self) -> Vec<String> {
+        let result = self.spawn_blocking(|inner| {
+            inner.with_index(|file_index| {
+                let languages = file_index.index
+                    .keys()
+                    .map(|(language, _class)| language.clone())
+                    .collect::<HashSet<_>>();
+                
+                Ok(languages.into_iter().collect())
+            })
+        }).await;
+        
+        result.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn saves_and_loads_classinfos() {
+        let filepath = std::env::temp_dir()
+            .join(format!("steam_tradeoffers_test_{}", std::process::id()))
+            .join("classinfos.tsv");
+        let store = FileClassInfoStore::new(&filepath);
+        let class = (440, 2674, Some(11040547));
+        let classinfo = include_str!("../../response/fixtures/classinfo_refined_metal.json");
+        let classinfos = HashMap::from([(class, classinfo.to_string())]);
+        
+        store.save_classinfos("english", &classinfos).await.unwrap();
+        
+        let loaded = store.load_classinfos("english", &[class, (440, 1, None)]).await;
+        
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].1.classid, 2674);
+        assert!(store.load_classinfos("german", &[class]).await.is_empty());
+        assert!(matches!(
+            store.save_classinfos("eng\tlish", &classinfos).await,
+            Err(FileError::InvalidLanguage(_)),
+        ));
+        
+        // the index is rebuilt from the file
+        let store = FileClassInfoStore::new(&filepath);
+        let loaded = store.load_classinfos("english", &[class]).await;
+        
+        assert_eq!(loaded.len(), 1);
+        
+        let _ = fs::remove_file(filepath);
+    }
+}
@@ -0,0 +1,60 @@
+mod directory;
+mod file;
+
+pub use directory::DirectoryClassInfoStore;
+pub use file::FileClassInfoStore;
+
+use super::types::ClassInfoFile;
+use crate::{error::FileError, types::ClassInfoClass};
+use async_trait::async_trait;
+use std::{fmt, collections::HashMap};
+
+/// Storage for [`ClassInfo`](crate::response::ClassInfo) data outside of memory. Classinfos
+/// are checked in the store before they are requested from the API, and classinfos from the API
+/// are saved to the store. Classinfos are stored separately for each language.
+#[async_trait]
+pub trait ClassInfoStore: fmt::Debug + Send + Sync {
+    /// Loads the classinfos in the given language for the given classes. Classes which are not
+    /// in the store are omitted from the result.
+    async fn load_classinfos(
+        &self,
+        language: &str,
+        classes: &[ClassInfoClass],
+    ) -> Vec<ClassInfoFile>;
+    
+    /// Saves classinfos in the given language. Each classinfo is given as its raw JSON string.
+    async fn save_classinfos(
+        &self,
+        language: &str,
+        classinfos: &HashMap<ClassInfoClass, String>,
+    ) -> Result<(), FileError>;
+    
+    /// The languages classinfos are stored in. Used when falling back to classinfos in other
+    /// languages with [`FallbackLanguages::Any`](super::FallbackLanguages::Any).
+    async fn languages(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Does not store classinfos. Classinfos are only kept in the in-memory [`ClassInfoCache`](super::ClassInfoCache).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoDiskClassInfoStore;
+
+#[async_trait]
+impl ClassInfoStore for NoDiskClassInfoStore {
+    async fn load_classinfos(
+        &self,
+        _language: &str,
+        _classes: &[ClassInfoClass],
+    ) -> Vec<ClassInfoFile> {
+        Vec::new()
+    }
+    
+    async fn save_classinfos(
+        &self,
+        _language: &str,
+        _classinfos: &HashMap<ClassInfoClass, String>,
+    ) -> Result<(), FileError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+use num_enum::{TryFromPrimitive, IntoPrimitive};
+use serde_repr::{Serialize_repr, Deserialize_repr};
+use strum_macros::{Display, EnumString};
+
+/// A way of confirming a login.
+#[derive(Serialize_repr, Deserialize_repr, Display, EnumString, Debug, PartialEq, TryFromPrimitive, IntoPrimitive, Clone, Copy)]
+#[repr(u8)]
+pub enum AuthSessionGuardType {
+    Unknown = 0,
+    None = 1,
+    EmailCode = 2,
+    DeviceCode = 3,
+    DeviceConfirmation = 4,
+    EmailConfirmation = 5,
+    MachineToken = 6,
+}
\ No newline at end of file
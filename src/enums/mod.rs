@@ -1,9 +1,11 @@
+mod auth_session_guard_type;
 mod confirmation_method;
 mod eresult;
 mod offer_filter;
 mod trade_offer_state;
 mod trade_status;
 
+pub use auth_session_guard_type::AuthSessionGuardType;
 pub use confirmation_method::ConfirmationMethod;
 pub use eresult::EResult;
 pub use offer_filter::OfferFilter;
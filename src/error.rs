@@ -1,4 +1,7 @@
-use crate::types::{AppId, ClassId, InstanceId, TradeOfferId};
+use crate::{
+    enums::EResult,
+    types::{AppId, ClassId, InstanceId, TradeOfferId},
+};
 use reqwest_middleware;
 use std::{fmt, num::ParseIntError};
 
@@ -12,6 +15,10 @@ pub enum FileError {
     JoinError,
     #[error("Path conversion to string failed")]
     PathError,
+    #[error("File is locked by another process")]
+    Locked,
+    #[error("Invalid language: {}", .0)]
+    InvalidLanguage(String),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -43,8 +50,22 @@ pub enum Error {
     MissingClassInfo(#[from] MissingClassInfoError),
     #[error("No confirmation for offer {}", .0)]
     NoConfirmationForOffer(TradeOfferId),
+    /// A confirmation in a failed batch was no longer pending when checked afterwards. It may 
+    /// have been handled by the batch or elsewhere, so whether it went through is unknown.
+    #[error("Confirmation {} is no longer pending, outcome unknown", .0)]
+    ConfirmationNoLongerPending(u64),
+    /// A confirmation in a failed batch could not be checked afterwards because getting the 
+    /// pending confirmations failed. Contains the ID and the error from getting confirmations.
+    #[error("Confirmation {} has an unknown status: {}", .0, .1)]
+    ConfirmationStatusUnknown(u64, String),
     #[error("Poll called too soon after last poll")]
     PollCalledTooSoon,
+    #[error("Invalid trade URL: {}", .0)]
+    TradeUrl(#[from] ParseTradeUrlError),
+    #[error("Error parsing URL: {}", .0)]
+    Url(#[from] url::ParseError),
+    #[error("Login error: {}", .0)]
+    Login(#[from] LoginError),
 }
 
 #[derive(thiserror::Error, Debug, PartialEq)]
@@ -105,7 +126,7 @@ impl TradeOfferError {
 
 impl From<&str> for TradeOfferError {
     fn from(message: &str) -> Self {
-        if let Some(code) = message.trim().split(' ').rev().next() {
+        if let Some(code) = message.trim().split(' ').next_back() {
             let mut chars = code.chars();
             
             if chars.next() != Some('(') {
@@ -151,6 +172,40 @@ impl fmt::Display for MissingClassInfoError {
     }
 }
 
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ParseTradeUrlError {
+    #[error("{}", .0)]
+    Url(#[from] url::ParseError),
+    #[error("Not a steamcommunity.com URL")]
+    InvalidHost,
+    #[error("Not a trade offer URL")]
+    InvalidPath,
+    #[error("Missing partner")]
+    MissingPartner,
+    #[error("Invalid partner")]
+    InvalidPartner,
+    #[error("Invalid token")]
+    InvalidToken,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum LoginError {
+    #[error("Invalid RSA key")]
+    InvalidRsaKey,
+    #[error("Password is too long to encrypt")]
+    PasswordTooLong,
+    #[error("Invalid shared secret")]
+    InvalidSharedSecret,
+    #[error("A Steam Guard code is required")]
+    SteamGuardCodeRequired,
+    #[error("Timed out waiting for the login to be approved")]
+    Timeout,
+    #[error("Login failed: {}", .0)]
+    EResult(EResult),
+    #[error("Login failed with EResult {}", .0)]
+    UnknownEResult(i32),
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ParseHtmlError {
     #[error("{}", .0)]
@@ -1,14 +1,22 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest::{header, cookie::CookieStore};
 use serde::de::DeserializeOwned;
 use lazy_regex::{regex_is_match, regex_captures};
-use crate::error::{TradeOfferError, Error};
+use crate::{
+    error::{TradeOfferError, Error, FileError},
+    middleware::{MiddlewareOptions, RateLimitMiddleware, RetryMiddleware},
+};
 use log::error;
 
 pub fn get_default_middleware<T>(
     cookie_store: Arc<T>,
     user_agent_string: &'static str,
+    options: &MiddlewareOptions,
 ) -> ClientWithMiddleware
 where
     T: CookieStore + 'static
@@ -23,8 +31,14 @@ where
         .build()
         .unwrap();
     
-    ClientBuilder::new(client)
-        .build()
+    // retries are added first so that each retry is also rate limited
+    let builder = ClientBuilder::new(client)
+        .with(RetryMiddleware(options.retry_policy));
+    
+    match &options.rate_limiter {
+        Some(rate_limiter) => builder.with(RateLimitMiddleware(Arc::clone(rate_limiter))),
+        None => builder,
+    }.build()
 }
 
 fn is_login(location_option: Option<&header::HeaderValue>) -> bool {
@@ -86,4 +100,35 @@ where
             }
         }
     }
+}
+
+fn get_temp_filepath(filepath: &Path) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        // In any reasonable setting this shouldn't panic...
+        .expect("Invalid system time")
+        .as_millis();
+    let mut filepath = filepath.to_path_buf().into_os_string();
+    
+    filepath.push(format!(".{}.temp", timestamp));
+    filepath.into()
+}
+
+/// Performs a basic atomic file write. Blocks the current thread.
+pub fn write_file_atomic(
+    filepath: &Path,
+    data: &[u8],
+) -> Result<(), FileError> {
+    let temp_filepath = get_temp_filepath(filepath);
+    
+    if let Err(error) = std::fs::write(&temp_filepath, data) {
+        // something went wrong writing to this file...
+        let _ = std::fs::remove_file(&temp_filepath);
+        
+        return Err(error.into());
+    }
+    
+    std::fs::rename(&temp_filepath, filepath)?;
+    
+    Ok(())
 }
\ No newline at end of file
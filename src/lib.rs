@@ -1,9 +1,10 @@
 mod manager;
-mod api;
 mod serializers;
 mod classinfo_cache;
 mod mobile_api;
 mod helpers;
+mod trade_url;
+mod middleware;
 
 pub mod enums;
 pub mod types;
@@ -11,13 +12,43 @@ pub mod time;
 pub mod request;
 pub mod response;
 pub mod error;
+pub mod api;
+pub mod login;
 
-pub use mobile_api::Confirmation;
-pub use classinfo_cache::ClassInfoCache;
+pub use mobile_api::{
+    Confirmation,
+    ConfirmationType,
+    ConfirmationResult,
+    ConfirmationDetails,
+    MobileAuthenticator,
+    TimeSync,
+};
+pub use classinfo_cache::{
+    ClassInfoCache,
+    ClassInfoFile,
+    ClassInfoStore,
+    NoDiskClassInfoStore,
+    DirectoryClassInfoStore,
+    FileClassInfoStore,
+    LanguageFallback,
+    FallbackLanguages,
+};
 pub use time::ServerTime;
+pub use trade_url::TradeUrl;
+pub use middleware::{RateLimit, RateLimiter, RetryPolicy, MiddlewareOptions};
+pub use api::SteamTradeOfferAPI;
 pub use manager::{
     TradeOfferManager,
     Poll,
+    PollChange,
+    PollEvent,
+    PollStream,
+    PollData,
+    PollDataStore,
+    InMemoryPollDataStore,
+    FilePollDataStore,
+    Session,
+    SessionProvider,
 };
 
 pub use steamid_ng::{self, SteamID};
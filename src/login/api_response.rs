@@ -0,0 +1,42 @@
+use serde::Deserialize;
+use crate::{enums::AuthSessionGuardType, serializers::string};
+
+/// The response from IAuthenticationService/GetPasswordRSAPublicKey.
+#[derive(Deserialize, Debug)]
+pub struct GetPasswordRSAPublicKeyResponse {
+    /// The modulus as a hex string.
+    pub publickey_mod: String,
+    /// The exponent as a hex string.
+    pub publickey_exp: String,
+    /// The timestamp of the key. Sent back with the encrypted password.
+    #[serde(with = "string")]
+    pub timestamp: u64,
+}
+
+/// A way of confirming a login.
+#[derive(Deserialize, Debug)]
+pub struct AllowedConfirmation {
+    pub confirmation_type: AuthSessionGuardType,
+}
+
+/// The response from IAuthenticationService/BeginAuthSessionViaCredentials.
+#[derive(Deserialize, Debug)]
+pub struct BeginAuthSessionResponse {
+    #[serde(with = "string")]
+    pub client_id: u64,
+    pub request_id: String,
+    #[serde(default)]
+    pub interval: f32,
+    #[serde(default)]
+    pub allowed_confirmations: Vec<AllowedConfirmation>,
+    #[serde(with = "string")]
+    pub steamid: u64,
+}
+
+/// The response from IAuthenticationService/PollAuthSessionStatus. The access token is only
+/// included once the login is approved.
+#[derive(Deserialize, Debug)]
+pub struct PollAuthSessionStatusResponse {
+    #[serde(default)]
+    pub access_token: Option<String>,
+}
@@ -0,0 +1,85 @@
+use crate::error::LoginError;
+use num_bigint::BigUint;
+use rand::Rng;
+
+/// Encrypts a password with an RSA public key given as hex strings, using PKCS#1 v1.5 padding.
+/// Returns the encrypted password encoded as base64.
+pub fn encrypt_password(
+    password: &str,
+    publickey_mod: &str,
+    publickey_exp: &str,
+) -> Result<String, LoginError> {
+    let modulus = BigUint::parse_bytes(publickey_mod.as_bytes(), 16)
+        .ok_or(LoginError::InvalidRsaKey)?;
+    let exponent = BigUint::parse_bytes(publickey_exp.as_bytes(), 16)
+        .ok_or(LoginError::InvalidRsaKey)?;
+    let key_size = modulus.bits().div_ceil(8) as usize;
+    let message = password.as_bytes();
+    
+    if key_size < 11 || message.len() > key_size - 11 {
+        return Err(LoginError::PasswordTooLong);
+    }
+    
+    let mut rng = rand::thread_rng();
+    let mut padded = Vec::with_capacity(key_size);
+    
+    padded.push(0x00);
+    padded.push(0x02);
+    
+    for _ in 0..(key_size - message.len() - 3) {
+        // padding bytes must be non-zero
+        padded.push(rng.gen_range(1..=255));
+    }
+    
+    padded.push(0x00);
+    padded.extend_from_slice(message);
+    
+    let encrypted = BigUint::from_bytes_be(&padded)
+        .modpow(&exponent, &modulus)
+        .to_bytes_be();
+    let mut bytes = vec![0; key_size - encrypted.len()];
+    
+    bytes.extend(encrypted);
+    
+    Ok(base64::encode(bytes))
+}
+
+/// Generates a random session ID.
+pub fn generate_sessionid() -> String {
+    rand::thread_rng()
+        .gen::<[u8; 12]>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const MODULUS: &str = "7d2be5742569abe235b6d2bdab82b610f5862282b9a1a75aac22f672cbf97c339a4af34718beb80c25953e352fe1e2db9283de56df4a1a7290c7f4e82761d45b";
+    const PRIVATE_EXPONENT: &str = "26f20c7f79d08a2964fb1050f157471cb9b7d56f0520f5f8314ce38f4e45becdc3af6fea95dfca232e980ff56034caa50f8632f74af8a80a989b970498e416c1";
+    
+    #[test]
+    fn encrypts_password() {
+        let encrypted = encrypt_password("hunter2", MODULUS, "010001").unwrap();
+        let bytes = base64::decode(encrypted).unwrap();
+        let modulus = BigUint::parse_bytes(MODULUS.as_bytes(), 16).unwrap();
+        let private_exponent = BigUint::parse_bytes(PRIVATE_EXPONENT.as_bytes(), 16).unwrap();
+        let decrypted = BigUint::from_bytes_be(&bytes)
+            .modpow(&private_exponent, &modulus)
+            .to_bytes_be();
+        
+        assert_eq!(bytes.len(), 64);
+        // the leading zero byte is dropped when converted back from a number
+        assert_eq!(decrypted[0], 0x02);
+        assert!(decrypted.ends_with(b"\x00hunter2"));
+    }
+    
+    #[test]
+    fn rejects_long_password() {
+        let password = "a".repeat(60);
+        
+        assert_eq!(encrypt_password(&password, MODULUS, "010001"), Err(LoginError::PasswordTooLong));
+    }
+}
@@ -0,0 +1,426 @@
+//! Logging in to Steam for a web session using the Steam authentication flow.
+
+mod api_response;
+mod helpers;
+
+use api_response::{
+    GetPasswordRSAPublicKeyResponse,
+    BeginAuthSessionResponse,
+    PollAuthSessionStatusResponse,
+};
+use helpers::{encrypt_password, generate_sessionid};
+use std::{sync::Arc, time::Duration};
+use reqwest::{cookie::Jar, header::{ORIGIN, REFERER}};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{Serialize, de::DeserializeOwned};
+use crate::{
+    SteamID,
+    Session,
+    MiddlewareOptions,
+    api::api_response::WebAPIResponse,
+    enums::{AuthSessionGuardType, EResult},
+    error::{Error, LoginError},
+    helpers::{get_default_middleware, parses_response},
+    mobile_api::{self, TimeSync},
+};
+
+const HOSTNAME: &str = "https://steamcommunity.com";
+const API_HOSTNAME: &str = "https://api.steampowered.com";
+const USER_AGENT_STRING: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/97.0.4692.71 Safari/537.36";
+/// The maximum number of times the auth session status is polled when finalizing a login.
+const MAX_POLL_ATTEMPTS: u32 = 10;
+
+/// Generates a Steam Guard code for the given server time from a base64-encoded shared secret.
+/// The current server time can be got from [`TimeSync::server_timestamp`].
+pub fn generate_auth_code(shared_secret: &str, time: i64) -> Result<String, LoginError> {
+    mobile_api::generate_auth_code(shared_secret, time)
+        .map_err(|_error| LoginError::InvalidSharedSecret)
+}
+
+/// A login in progress. Created by [`SteamLogin::begin_auth_session`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthSession {
+    pub client_id: u64,
+    pub request_id: String,
+    pub steamid: SteamID,
+    /// How often the status of the login should be polled, in seconds.
+    pub interval: f32,
+    /// The ways the login can be confirmed.
+    pub allowed_confirmations: Vec<AuthSessionGuardType>,
+}
+
+impl AuthSession {
+    /// Checks if the login can be confirmed using the given guard type.
+    pub fn allows(&self, guard_type: AuthSessionGuardType) -> bool {
+        self.allowed_confirmations.contains(&guard_type)
+    }
+}
+
+/// Client for logging in to Steam.
+#[derive(Debug)]
+pub struct SteamLogin {
+    client: ClientWithMiddleware,
+    hostname: String,
+    api_hostname: String,
+    time_sync: Arc<TimeSync>,
+}
+
+impl Default for SteamLogin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SteamLogin {
+    pub fn new() -> Self {
+        Self::with_hostnames(HOSTNAME, API_HOSTNAME)
+    }
+    
+    /// Creates a client which makes requests to the given hosts in place of Steam's e.g. a local
+    /// stand-in server for testing.
+    pub fn with_hostnames(hostname: &str, api_hostname: &str) -> Self {
+        Self {
+            client: get_default_middleware(
+                Arc::new(Jar::default()),
+                USER_AGENT_STRING,
+                &MiddlewareOptions::default(),
+            ),
+            hostname: hostname.to_string(),
+            api_hostname: api_hostname.to_string(),
+            time_sync: Arc::new(TimeSync::new(api_hostname)),
+        }
+    }
+    
+    /// Uses the given [`TimeSync`] for the server time when generating Steam Guard codes e.g. 
+    /// one shared with a [`crate::TradeOfferManager`].
+    pub fn time_sync(mut self, time_sync: Arc<TimeSync>) -> Self {
+        self.time_sync = time_sync;
+        self
+    }
+    
+    fn get_api_url(
+        &self,
+        interface: &str,
+        method: &str,
+        version: usize,
+    ) -> String {
+        format!("{}/{}/{}/v{}", self.api_hostname, interface, method, version)
+    }
+    
+    async fn call_get<P, R>(
+        &self,
+        method: &str,
+        params: &P,
+    ) -> Result<R, Error>
+    where
+        P: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let uri = self.get_api_url("IAuthenticationService", method, 1);
+        let response = self.client.get(&uri)
+            .header(ORIGIN, &self.hostname)
+            .header(REFERER, format!("{}/", self.hostname))
+            .query(params)
+            .send()
+            .await?;
+        
+        check_eresult(&response)?;
+        
+        let body: WebAPIResponse<R> = parses_response(response).await?;
+        
+        Ok(body.response)
+    }
+    
+    async fn call_post<P, R>(
+        &self,
+        method: &str,
+        params: &P,
+    ) -> Result<R, Error>
+    where
+        P: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let uri = self.get_api_url("IAuthenticationService", method, 1);
+        let response = self.client.post(&uri)
+            .header(ORIGIN, &self.hostname)
+            .header(REFERER, format!("{}/", self.hostname))
+            .form(params)
+            .send()
+            .await?;
+        
+        check_eresult(&response)?;
+        
+        let body: WebAPIResponse<R> = parses_response(response).await?;
+        
+        Ok(body.response)
+    }
+    
+    /// Begins a login with an account name and password. The login must then be confirmed
+    /// according to [`AuthSession::allowed_confirmations`] before calling
+    /// [`SteamLogin::finalize`].
+    pub async fn begin_auth_session(
+        &self,
+        account_name: &str,
+        password: &str,
+    ) -> Result<AuthSession, Error> {
+        #[derive(Serialize, Debug)]
+        struct RSAKeyParams<'a> {
+            account_name: &'a str,
+        }
+        
+        #[derive(Serialize, Debug)]
+        struct BeginAuthSessionParams<'a> {
+            account_name: &'a str,
+            encrypted_password: &'a str,
+            encryption_timestamp: u64,
+            remember_login: bool,
+            persistence: u8,
+            website_id: &'a str,
+        }
+        
+        let key: GetPasswordRSAPublicKeyResponse = self.call_get("GetPasswordRSAPublicKey", &RSAKeyParams {
+            account_name,
+        }).await?;
+        let encrypted_password = encrypt_password(
+            password,
+            &key.publickey_mod,
+            &key.publickey_exp,
+        )?;
+        let body: BeginAuthSessionResponse = self.call_post("BeginAuthSessionViaCredentials", &BeginAuthSessionParams {
+            account_name,
+            encrypted_password: &encrypted_password,
+            encryption_timestamp: key.timestamp,
+            remember_login: true,
+            // persistent session
+            persistence: 1,
+            website_id: "Community",
+        }).await?;
+        
+        Ok(AuthSession {
+            client_id: body.client_id,
+            request_id: body.request_id,
+            steamid: SteamID::from(body.steamid),
+            interval: body.interval,
+            allowed_confirmations: body.allowed_confirmations
+                .into_iter()
+                .map(|allowed| allowed.confirmation_type)
+                .collect(),
+        })
+    }
+    
+    /// Confirms a login using a Steam Guard code from the mobile authenticator or email.
+    pub async fn submit_steam_guard_code(
+        &self,
+        auth_session: &AuthSession,
+        code: &str,
+        code_type: AuthSessionGuardType,
+    ) -> Result<(), Error> {
+        #[derive(Serialize, Debug)]
+        struct UpdateAuthSessionParams<'a> {
+            client_id: u64,
+            steamid: u64,
+            code: &'a str,
+            code_type: u8,
+        }
+        
+        let _body: serde_json::Value = self.call_post("UpdateAuthSessionWithSteamGuardCode", &UpdateAuthSessionParams {
+            client_id: auth_session.client_id,
+            steamid: u64::from(auth_session.steamid),
+            code,
+            code_type: code_type.into(),
+        }).await?;
+        
+        Ok(())
+    }
+    
+    /// Waits for a login to be confirmed, then finalizes it into a web session.
+    pub async fn finalize(
+        &self,
+        auth_session: &AuthSession,
+    ) -> Result<Session, Error> {
+        #[derive(Serialize, Debug)]
+        struct PollAuthSessionStatusParams<'a> {
+            client_id: u64,
+            request_id: &'a str,
+        }
+        
+        for attempt in 0..MAX_POLL_ATTEMPTS {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_secs_f32(auth_session.interval.max(0.0))).await;
+            }
+            
+            let body: PollAuthSessionStatusResponse = self.call_post("PollAuthSessionStatus", &PollAuthSessionStatusParams {
+                client_id: auth_session.client_id,
+                request_id: &auth_session.request_id,
+            }).await?;
+            
+            if let Some(access_token) = body.access_token {
+                let sessionid = generate_sessionid();
+                let cookies = vec![
+                    format!("steamLoginSecure={}%7C%7C{}", u64::from(auth_session.steamid), access_token),
+                    format!("sessionid={}", sessionid),
+                ];
+                
+                return Ok(Session {
+                    sessionid,
+                    cookies,
+                });
+            }
+        }
+        
+        Err(LoginError::Timeout.into())
+    }
+    
+    /// Logs in with an account name and password. If the login requires a code from the mobile
+    /// authenticator, one is generated from `shared_secret`. Logins requiring an email code
+    /// fail with [`LoginError::SteamGuardCodeRequired`]; use
+    /// [`SteamLogin::begin_auth_session`] and [`SteamLogin::submit_steam_guard_code`] for these.
+    pub async fn login(
+        &self,
+        account_name: &str,
+        password: &str,
+        shared_secret: Option<&str>,
+    ) -> Result<Session, Error> {
+        let auth_session = self.begin_auth_session(account_name, password).await?;
+        
+        if auth_session.allows(AuthSessionGuardType::DeviceCode) {
+            let shared_secret = shared_secret.ok_or(LoginError::SteamGuardCodeRequired)?;
+            
+            self.time_sync.sync_if_needed(&self.client).await;
+            
+            let code = generate_auth_code(shared_secret, self.time_sync.server_timestamp())?;
+            
+            self.submit_steam_guard_code(&auth_session, &code, AuthSessionGuardType::DeviceCode).await?;
+        } else if auth_session.allows(AuthSessionGuardType::EmailCode) {
+            return Err(LoginError::SteamGuardCodeRequired.into());
+        }
+        
+        self.finalize(&auth_session).await
+    }
+}
+
+fn check_eresult(response: &reqwest::Response) -> Result<(), LoginError> {
+    let eresult = response.headers()
+        .get("x-eresult")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<i32>().ok());
+    
+    match eresult {
+        None | Some(1) => Ok(()),
+        Some(code) => {
+            let eresult = u8::try_from(code)
+                .ok()
+                .and_then(|code| EResult::try_from(code).ok());
+            
+            match eresult {
+                Some(eresult) => Err(LoginError::EResult(eresult)),
+                None => Err(LoginError::UnknownEResult(code)),
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    
+    const MODULUS: &str = "7d2be5742569abe235b6d2bdab82b610f5862282b9a1a75aac22f672cbf97c339a4af34718beb80c25953e352fe1e2db9283de56df4a1a7290c7f4e82761d45b";
+    const SHARED_SECRET: &str = "MDEyMzQ1Njc4OTAxMjM0NTY3ODk=";
+    
+    /// Starts a stand-in server for the authentication service. Returns its address and the
+    /// methods that were called.
+    async fn start_server(eresult: i32) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let server_calls = Arc::clone(&calls);
+        
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buffer = vec![0; 8192];
+                let mut read = 0;
+                
+                // read until the end of the headers and the whole body
+                loop {
+                    read += stream.read(&mut buffer[read..]).await.unwrap();
+                    
+                    let request = String::from_utf8_lossy(&buffer[..read]);
+                    
+                    if let Some((head, body)) = request.split_once("\r\n\r\n") {
+                        let content_length = head.lines()
+                            .find_map(|line| line.to_lowercase().strip_prefix("content-length: ").map(String::from))
+                            .and_then(|length| length.parse::<usize>().ok())
+                            .unwrap_or(0);
+                        
+                        if body.len() >= content_length {
+                            break;
+                        }
+                    }
+                }
+                
+                let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+                let path = request.split(' ').nth(1).unwrap();
+                let method = path.split('/').nth(2).unwrap().to_string();
+                let body = match method.as_str() {
+                    "GetPasswordRSAPublicKey" => format!(r#"{{"response":{{"publickey_mod":"{}","publickey_exp":"010001","timestamp":"1234"}}}}"#, MODULUS),
+                    "BeginAuthSessionViaCredentials" => String::from(r#"{"response":{"client_id":"1","request_id":"cmVxdWVzdA==","interval":0,"allowed_confirmations":[{"confirmation_type":3}],"steamid":"76561198080179568"}}"#),
+                    "PollAuthSessionStatus" => String::from(r#"{"response":{"refresh_token":"refresh","access_token":"access"}}"#),
+                    "QueryTime" => String::from(r#"{"response":{"server_time":"1644323205"}}"#),
+                    _ => String::from(r#"{"response":{}}"#),
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nX-eresult: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    eresult,
+                    body.len(),
+                    body,
+                );
+                
+                server_calls.lock().unwrap().push(method);
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        
+        (address, calls)
+    }
+    
+    #[tokio::test]
+    async fn logs_in() {
+        let (address, calls) = start_server(1).await;
+        let login = SteamLogin::with_hostnames(&address, &address);
+        let session = login.login("account", "password", Some(SHARED_SECRET)).await.unwrap();
+        
+        assert_eq!(session.cookies[0], "steamLoginSecure=76561198080179568%7C%7Caccess");
+        assert_eq!(session.cookies[1], format!("sessionid={}", session.sessionid));
+        assert_eq!(*calls.lock().unwrap(), vec![
+            "GetPasswordRSAPublicKey",
+            "BeginAuthSessionViaCredentials",
+            "QueryTime",
+            "UpdateAuthSessionWithSteamGuardCode",
+            "PollAuthSessionStatus",
+        ]);
+    }
+    
+    #[tokio::test]
+    async fn requires_shared_secret_for_device_code() {
+        let (address, _calls) = start_server(1).await;
+        let login = SteamLogin::with_hostnames(&address, &address);
+        let error = login.login("account", "password", None).await.unwrap_err();
+        
+        assert!(matches!(error, Error::Login(LoginError::SteamGuardCodeRequired)));
+    }
+    
+    #[tokio::test]
+    async fn fails_on_eresult() {
+        let (address, _calls) = start_server(5).await;
+        let login = SteamLogin::with_hostnames(&address, &address);
+        let error = login.login("account", "wrong password", None).await.unwrap_err();
+        
+        assert!(matches!(error, Error::Login(LoginError::EResult(EResult::InvalidPassword))));
+    }
+}
@@ -1,15 +1,58 @@
-use super::TradeOfferManager;
-use crate::{SteamID, ClassInfoCache};
-use std::sync::{Mutex, Arc};
+use super::{
+    TradeOfferManager,
+    PollData,
+    PollDataStore,
+    InMemoryPollDataStore,
+    FilePollDataStore,
+    SessionProvider,
+};
+use crate::{
+    SteamID,
+    error::FileError,
+    ClassInfoCache,
+    MobileAuthenticator,
+    RateLimiter,
+    RetryPolicy,
+    classinfo_cache::{
+        ClassInfoStore,
+        NoDiskClassInfoStore,
+        DirectoryClassInfoStore,
+        LanguageFallback,
+    },
+};
+use std::{path::PathBuf, sync::{Mutex, Arc}};
 use chrono::Duration;
+use log::warn;
 
 pub struct TradeOfferManagerBuilder {
     pub steamid: SteamID,
     pub key: String,
     pub identity_secret: Option<String>,
+    /// The device ID used for confirmations. Derived from the SteamID by default.
+    pub device_id: Option<String>,
     pub language: String,
     pub classinfo_cache: Arc<Mutex<ClassInfoCache>>,
+    /// Where classinfos are stored outside of memory. Classinfos are not stored by default.
+    pub classinfo_store: Arc<dyn ClassInfoStore>,
+    /// Whether cached classinfos in other languages can be used. Only classinfos in the 
+    /// manager's language are used by default.
+    pub classinfo_language_fallback: LanguageFallback,
+    /// Whether classinfos are requested along with offers rather than requested separately.
+    /// Disabled by default.
+    pub get_descriptions: bool,
+    /// Whether the poll loop checks the offers summary before getting offers. Disabled by
+    /// default.
+    pub poll_with_summary: bool,
     pub cancel_duration: Option<Duration>,
+    /// Where poll data is stored. Poll data is kept in memory by default.
+    pub poll_data_store: Arc<dyn PollDataStore>,
+    /// Limits requests per host. Requests are not limited by default.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// How failed GET requests are retried. Uses [`RetryPolicy::default`] by default.
+    pub retry_policy: RetryPolicy,
+    /// Provides new sessions when the session expires. Requests fail with
+    /// [`Error::NotLoggedIn`](crate::error::Error::NotLoggedIn) by default.
+    pub session_provider: Option<Arc<dyn SessionProvider>>,
 }
 
 impl TradeOfferManagerBuilder {
@@ -21,9 +64,18 @@ impl TradeOfferManagerBuilder {
             steamid,
             key,
             identity_secret: None,
+            device_id: None,
             language: String::from("english"),
             classinfo_cache: Arc::new(Mutex::new(ClassInfoCache::default())),
+            classinfo_store: Arc::new(NoDiskClassInfoStore),
+            classinfo_language_fallback: LanguageFallback::default(),
+            get_descriptions: false,
+            poll_with_summary: false,
             cancel_duration: None,
+            poll_data_store: Arc::new(InMemoryPollDataStore::new()),
+            rate_limiter: None,
+            retry_policy: RetryPolicy::default(),
+            session_provider: None,
         }
     }
     
@@ -31,6 +83,24 @@ impl TradeOfferManagerBuilder {
         self.identity_secret = Some(identity_secret);
         self
     }
+    
+    /// Sets the device ID used for confirmations.
+    pub fn device_id(mut self, device_id: String) -> Self {
+        self.device_id = Some(device_id);
+        self
+    }
+    
+    /// Uses the identity secret and device ID from a mobile authenticator e.g. one loaded from a 
+    /// maFile.
+    pub fn mobile_authenticator(mut self, authenticator: MobileAuthenticator) -> Self {
+        self.identity_secret = Some(authenticator.identity_secret);
+        
+        if authenticator.device_id.is_some() {
+            self.device_id = authenticator.device_id;
+        }
+        
+        self
+    }
 
     pub fn language(mut self, language: String) -> Self {
         self.language = language;
@@ -42,12 +112,94 @@ impl TradeOfferManagerBuilder {
         self
     }
     
+    /// Stores classinfos as files in the given directory using a [`DirectoryClassInfoStore`].
+    pub fn classinfo_directory<P: Into<PathBuf>>(mut self, directory: P) -> Self {
+        self.classinfo_store = Arc::new(DirectoryClassInfoStore::new(directory));
+        self
+    }
+    
+    /// Sets the store used for classinfos.
+    pub fn classinfo_store(mut self, classinfo_store: Arc<dyn ClassInfoStore>) -> Self {
+        self.classinfo_store = classinfo_store;
+        self
+    }
+    
+    /// Sets when cached classinfos in other languages can be used.
+    pub fn classinfo_language_fallback(mut self, classinfo_language_fallback: LanguageFallback) -> Self {
+        self.classinfo_language_fallback = classinfo_language_fallback;
+        self
+    }
+    
+    /// Requests classinfos along with offers when getting offers. This saves a request for each
+    /// chunk of classes that aren't cached, but makes each offers response larger.
+    pub fn get_descriptions(mut self, get_descriptions: bool) -> Self {
+        self.get_descriptions = get_descriptions;
+        self
+    }
+    
+    /// Checks the offers summary in the poll loop started with 
+    /// [`TradeOfferManager::start_polling`] and skips getting offers when nothing has changed.
+    /// A full update is still done every five minutes.
+    pub fn poll_with_summary(mut self, poll_with_summary: bool) -> Self {
+        self.poll_with_summary = poll_with_summary;
+        self
+    }
+    
     pub fn cancel_duration(mut self, duration: Duration) -> Self {
         self.cancel_duration = Some(duration);
         self
     }
     
+    /// Stores poll data in a file at the given path using a [`FilePollDataStore`].
+    pub fn poll_data_path<P: Into<PathBuf>>(mut self, filepath: P) -> Self {
+        self.poll_data_store = Arc::new(FilePollDataStore::new(filepath));
+        self
+    }
+    
+    /// Sets the store used for poll data.
+    pub fn poll_data_store(mut self, poll_data_store: Arc<dyn PollDataStore>) -> Self {
+        self.poll_data_store = poll_data_store;
+        self
+    }
+    
+    /// Limits requests per host using the given [`RateLimiter`]. The same rate limiter can be 
+    /// given to several managers to keep them under the same limits.
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+    
+    /// Sets how failed GET requests are retried. Use [`RetryPolicy::none`] to disable retrying.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+    
+    /// Refreshes the session using the given [`SessionProvider`] when it expires.
+    pub fn session_provider(mut self, session_provider: Arc<dyn SessionProvider>) -> Self {
+        self.session_provider = Some(session_provider);
+        self
+    }
+    
+    /// Builds the manager. If the stored poll data cannot be loaded, a warning is logged and the 
+    /// manager starts with empty poll data. Use [`TradeOfferManagerBuilder::try_build`] to 
+    /// handle this instead.
     pub fn build(self) -> TradeOfferManager {
-        TradeOfferManager::from(self)
+        let poll_data = match self.poll_data_store.load() {
+            // poll data is only empty when nothing has been stored yet
+            Ok(poll_data) => poll_data.unwrap_or_default(),
+            Err(error) => {
+                warn!("Error loading poll data: {}", error);
+                PollData::default()
+            },
+        };
+        
+        TradeOfferManager::from_builder(self, poll_data)
+    }
+    
+    /// Builds the manager. Fails if the stored poll data cannot be loaded e.g. when another 
+    /// process holds the lock on the poll data file.
+    pub fn try_build(self) -> Result<TradeOfferManager, FileError> {
+        TradeOfferManager::try_from(self)
     }
 }
\ No newline at end of file
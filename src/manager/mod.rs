@@ -1,73 +1,82 @@
 mod poll;
 mod poll_data;
-mod file;
+mod poll_data_store;
+mod poller;
 mod builder;
+mod session;
 
 pub use builder::TradeOfferManagerBuilder;
-pub use poll::Poll;
+pub use session::{Session, SessionProvider};
+pub use poll::{Poll, PollChange, PollEvent, PollStream};
+pub use poll_data::PollData;
+pub use poll_data_store::{PollDataStore, InMemoryPollDataStore, FilePollDataStore};
 
-use poll_data::PollData;
-use std::{cmp, sync::{Arc, RwLock}};
-use chrono::Duration;
+use poller::Poller;
+use session::SessionRefresher;
+use std::{future::Future, sync::{Arc, RwLock, Mutex}};
 use crate::{
-    error::Error,
+    error::{Error, FileError, MissingClassInfoError},
     ServerTime,
+    TradeUrl,
+    MiddlewareOptions,
     enums::{OfferFilter, TradeOfferState},
-    time,
     response,
     request,
     api::SteamTradeOfferAPI,
-    error::FileError,
-    mobile_api::{MobileAPI, Confirmation},
+    mobile_api::{
+        MobileAPI,
+        TimeSync,
+        Confirmation,
+        ConfirmationResult,
+        ConfirmationDetails,
+        RawConfirmationDetails,
+    },
     types::{
         AppId,
         ContextId,
         TradeOfferId,
+        TradeId,
     },
 };
 use steamid_ng::SteamID;
 use url::ParseError;
-use reqwest::cookie::Jar;
+use reqwest::{StatusCode, cookie::Jar};
+use log::warn;
+
+/// Checks whether an error means the Steam Web API couldn't be used, as opposed to an answer 
+/// from it such as an invalid token.
+fn is_webapi_unavailable(error: &Error) -> bool {
+    match error {
+        Error::Reqwest(error) => error.is_timeout() || error.is_connect() || error.is_request(),
+        Error::ReqwestMiddleware(_) => true,
+        Error::Http(response) => {
+            response.status().is_server_error() || response.status() == StatusCode::FORBIDDEN
+        },
+        _ => false,
+    }
+}
 
 /// Manager which includes functionality for interacting with trade offers, confirmations and 
 /// inventories.
 #[derive(Debug)]
 pub struct TradeOfferManager {
-    steamid: SteamID,
     // manager facades api
-    api: SteamTradeOfferAPI,
-    mobile_api: MobileAPI,
-    poll_data: Arc<RwLock<PollData>>,
-    cancel_duration: Option<Duration>,
+    api: Arc<SteamTradeOfferAPI>,
+    mobile_api: Arc<MobileAPI>,
+    poller: Arc<Poller>,
+    session: Arc<SessionRefresher>,
 }
 
-impl From<TradeOfferManagerBuilder> for TradeOfferManager {
-    fn from(builder: TradeOfferManagerBuilder) -> Self {
-        let cookies = Arc::new(Jar::default());
-        let steamid = builder.steamid;
-        let identity_secret = builder.identity_secret;
-        let poll_data = file::load_poll_data(&steamid).unwrap_or_else(|_| PollData::new());
-        let language = builder.language;
+impl TryFrom<TradeOfferManagerBuilder> for TradeOfferManager {
+    type Error = FileError;
+    
+    /// Builds the manager. Fails if the stored poll data cannot be loaded e.g. when another 
+    /// process holds the lock on the poll data file.
+    fn try_from(builder: TradeOfferManagerBuilder) -> Result<Self, Self::Error> {
+        // poll data is only empty when nothing has been stored yet
+        let poll_data = builder.poll_data_store.load()?.unwrap_or_default();
         
-        Self {
-            steamid,
-            api: SteamTradeOfferAPI::new(
-                Arc::clone(&cookies),
-                steamid,
-                builder.key,
-                language.clone(),
-                identity_secret.clone(),
-                builder.classinfo_cache,
-            ),
-            mobile_api: MobileAPI::new(
-                cookies,
-                steamid,
-                language,
-                identity_secret,
-            ),
-            poll_data: Arc::new(RwLock::new(poll_data)),
-            cancel_duration: builder.cancel_duration,
-        }
+        Ok(Self::from_builder(builder, poll_data))
     }
 }
 
@@ -80,6 +89,76 @@ impl TradeOfferManager {
         TradeOfferManagerBuilder::new(steamid, key)
     }
     
+    /// Builds the manager with the given poll data.
+    fn from_builder(builder: TradeOfferManagerBuilder, poll_data: PollData) -> Self {
+        let cookies = Arc::new(Jar::default());
+        let steamid = builder.steamid;
+        let identity_secret = builder.identity_secret;
+        let poll_data_store = builder.poll_data_store;
+        let language = builder.language;
+        let middleware_options = MiddlewareOptions {
+            rate_limiter: builder.rate_limiter,
+            retry_policy: builder.retry_policy,
+        };
+        let mut api_builder = SteamTradeOfferAPI::builder(steamid, builder.key)
+            .cookies(Arc::clone(&cookies))
+            .language(language.clone())
+            .classinfo_cache(builder.classinfo_cache)
+            .classinfo_store(builder.classinfo_store)
+            .classinfo_language_fallback(builder.classinfo_language_fallback)
+            .get_descriptions(builder.get_descriptions)
+            .retry_policy(middleware_options.retry_policy);
+        
+        if let Some(rate_limiter) = &middleware_options.rate_limiter {
+            api_builder = api_builder.rate_limiter(Arc::clone(rate_limiter));
+        }
+        
+        if let Some(identity_secret) = &identity_secret {
+            api_builder = api_builder.identity_secret(identity_secret.clone());
+        }
+        
+        let api = Arc::new(api_builder.build());
+        let mobile_api = Arc::new(MobileAPI::new(
+            cookies,
+            steamid,
+            language,
+            identity_secret,
+            builder.device_id,
+            &middleware_options,
+        ));
+        let time_sync = Arc::clone(&mobile_api.time_sync);
+        let session = Arc::new(SessionRefresher::new(builder.session_provider, {
+            let api = Arc::clone(&api);
+            let mobile_api = Arc::clone(&mobile_api);
+            
+            move |session| {
+                api.set_session(&session.sessionid, &session.cookies)?;
+                mobile_api.set_session(&session.sessionid, &session.cookies)
+            }
+        }));
+        
+        Self {
+            api: Arc::clone(&api),
+            mobile_api,
+            poller: Arc::new(Poller {
+                api,
+                session: Arc::clone(&session),
+                time_sync,
+                poll_data: Arc::new(RwLock::new(poll_data)),
+                poll_data_store,
+                cancel_duration: builder.cancel_duration,
+                poll_with_summary: builder.poll_with_summary,
+                last_summary: Mutex::new(None),
+            }),
+            session,
+        }
+    }
+    
+    /// The low-level client used for requests. It shares the manager's session and caches.
+    pub fn api(&self) -> &SteamTradeOfferAPI {
+        &self.api
+    }
+    
     /// Sets the session and cookies.
     pub fn set_session(
         &self,
@@ -92,16 +171,27 @@ impl TradeOfferManager {
         Ok(())
     }
     
+    /// Calls `f`, then if the session has expired and a [`SessionProvider`] is set, refreshes 
+    /// the session and calls `f` once more.
+    async fn with_session<T, F, Fut>(&self, f: F) -> Result<T, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        self.session.with_session(f).await
+    }
+    
     /// Counters an existing offer.
     pub async fn counter_offer(
         &self,
         offer: &mut response::trade_offer::TradeOffer,
         counter_offer: &request::trade_offer::NewTradeOffer,
     ) -> Result<response::sent_offer::SentOffer, Error> {
-        let sent_offer = self.api.send_offer(
+        let tradeofferid = offer.tradeofferid;
+        let sent_offer = self.with_session(|| self.api.send_offer(
             counter_offer,
-            Some(offer.tradeofferid),
-        ).await?;
+            Some(tradeofferid),
+        )).await?;
         
         offer.trade_offer_state = TradeOfferState::Countered;
         
@@ -113,7 +203,38 @@ impl TradeOfferManager {
         &self,
         offer: &request::trade_offer::NewTradeOffer,
     ) -> Result<response::sent_offer::SentOffer, Error> {
-        self.api.send_offer(offer, None).await
+        self.with_session(|| self.api.send_offer(offer, None)).await
+    }
+    
+    /// Checks an offer against our inventory and the partner's inventory before sending it. 
+    /// Returns a list of problems which would cause the offer to fail, which is empty if none 
+    /// were found.
+    pub async fn validate_offer(
+        &self,
+        offer: &request::trade_offer::NewTradeOffer,
+    ) -> Result<Vec<request::trade_offer::OfferProblem>, Error> {
+        let mut our_inventory = Vec::new();
+        let mut their_inventory = Vec::new();
+        
+        for (appid, contextid) in offer.inventory_contexts(request::trade_offer::OfferSide::Give) {
+            our_inventory.extend(self.with_session(|| self.api.get_inventory(
+                &self.mobile_api.steamid,
+                appid,
+                contextid,
+                false,
+            )).await?);
+        }
+        
+        for (appid, contextid) in offer.inventory_contexts(request::trade_offer::OfferSide::Receive) {
+            their_inventory.extend(self.with_session(|| self.api.get_partner_inventory(
+                &offer.partner,
+                appid,
+                contextid,
+                &offer.token,
+            )).await?);
+        }
+        
+        Ok(offer.validate(&our_inventory, &their_inventory))
     }
     
     /// Accepts an offer.
@@ -127,7 +248,9 @@ impl TradeOfferManager {
             return Err(Error::Parameter("Cannot accept an offer that is not active"));
         }
 
-        let accepted_offer = self.api.accept_offer(offer.tradeofferid, &offer.partner).await?;
+        let tradeofferid = offer.tradeofferid;
+        let partner = offer.partner;
+        let accepted_offer = self.with_session(|| self.api.accept_offer(tradeofferid, &partner)).await?;
         offer.trade_offer_state = TradeOfferState::Accepted;
         
         Ok(accepted_offer)
@@ -142,7 +265,9 @@ impl TradeOfferManager {
             return Err(Error::Parameter("Cannot cancel an offer we did not create"));
         }
         
-        self.api.cancel_offer(offer.tradeofferid).await?;
+        let tradeofferid = offer.tradeofferid;
+        
+        self.with_session(|| self.api.cancel_offer(tradeofferid)).await?;
         offer.trade_offer_state = TradeOfferState::Canceled;
         
         Ok(())
@@ -157,7 +282,9 @@ impl TradeOfferManager {
             return Err(Error::Parameter("Cannot decline an offer we created"));
         }
         
-        self.api.decline_offer(offer.tradeofferid).await?;
+        let tradeofferid = offer.tradeofferid;
+        
+        self.with_session(|| self.api.decline_offer(tradeofferid)).await?;
         offer.trade_offer_state = TradeOfferState::Declined;
         
         Ok(())
@@ -171,7 +298,7 @@ impl TradeOfferManager {
         contextid: ContextId,
         tradable_only: bool,
     ) -> Result<Vec<response::asset::Asset>, Error> {
-        self.api.get_inventory_old(steamid, appid, contextid, tradable_only).await
+        self.with_session(|| self.api.get_inventory_old(steamid, appid, contextid, tradable_only)).await
     }
     
     /// Gets a user's inventory.
@@ -182,7 +309,19 @@ impl TradeOfferManager {
         contextid: ContextId,
         tradable_only: bool,
     ) -> Result<Vec<response::asset::Asset>, Error> {
-        self.api.get_inventory(steamid, appid, contextid, tradable_only).await
+        self.with_session(|| self.api.get_inventory(steamid, appid, contextid, tradable_only)).await
+    }
+    
+    /// Gets a trade partner's inventory from the trade offer page. Use this for partners whose
+    /// inventory is only visible to trade partners.
+    pub async fn get_partner_inventory(
+        &self,
+        partner: &SteamID,
+        appid: AppId,
+        contextid: ContextId,
+        token: &Option<String>,
+    ) -> Result<Vec<response::asset::Asset>, Error> {
+        self.with_session(|| self.api.get_partner_inventory(partner, appid, contextid, token)).await
     }
     
     /// Gets the user's details for trading.
@@ -192,14 +331,63 @@ impl TradeOfferManager {
         partner: &SteamID,
         token: &Option<String>,
     ) -> Result<response::user_details::UserDetails, Error> {
-        self.api.get_user_details(tradeofferid, partner, token).await
+        self.with_session(|| self.api.get_user_details(tradeofferid, partner, token)).await
+    }
+    
+    /// Gets the trade hold durations for trading with a partner. Uses the Steam Web API, falling
+    /// back to the trade offer page if the Web API is unavailable. Only the escrow durations are 
+    /// included either way.
+    pub async fn get_trade_hold_durations(
+        &self,
+        partner: &SteamID,
+        token: &Option<String>,
+    ) -> Result<response::user_details::UserDetails, Error> {
+        match self.api.get_trade_hold_durations(partner, token).await {
+            Err(error) if is_webapi_unavailable(&error) => {
+                warn!("Error getting trade hold durations: {}", error);
+                
+                let user_details = self.with_session(|| self.api.get_user_details(&None, partner, token)).await?;
+                
+                Ok(response::user_details::UserDetails {
+                    me: None,
+                    them: None,
+                    ..user_details
+                })
+            },
+            result => result,
+        }
+    }
+    
+    /// Gets our trade URL.
+    pub async fn get_trade_url(&self) -> Result<TradeUrl, Error> {
+        self.with_session(|| self.api.get_trade_url()).await
+    }
+    
+    /// Generates a new token for our trade URL, invalidating the old one. Returns the new trade
+    /// URL.
+    pub async fn regenerate_trade_url(&self) -> Result<TradeUrl, Error> {
+        let token = self.with_session(|| self.api.regenerate_trade_url_token()).await?;
+        
+        Ok(TradeUrl::new(self.mobile_api.steamid, Some(token)))
+    }
+    
+    /// Syncs the time with Steam. The offset is shared by confirmations and the poller. The time 
+    /// is otherwise synced when needed for confirmations.
+    pub async fn sync_time(&self) -> Result<(), Error> {
+        self.mobile_api.sync_time().await
+    }
+    
+    /// Gets the [`TimeSync`] used for the server time e.g. for generating Steam Guard codes with 
+    /// [`crate::MobileAuthenticator::generate_auth_code`].
+    pub fn time_sync(&self) -> Arc<TimeSync> {
+        Arc::clone(&self.mobile_api.time_sync)
     }
     
     /// Gets trade confirmations.
     pub async fn get_trade_confirmations(
         &self,
     ) -> Result<Vec<Confirmation>, Error> {
-        self.mobile_api.get_trade_confirmations().await
+        self.with_session(|| self.mobile_api.get_trade_confirmations()).await
     }
     
     /// Confirms a trade offer.
@@ -227,24 +415,68 @@ impl TradeOfferManager {
         }
     }
     
+    /// Gets what a confirmation will do from its details page. For trades the linked offer is 
+    /// loaded, and for market listings the listed item is described from the page, falling back 
+    /// to requesting its classinfo.
+    pub async fn get_confirmation_details(
+        &self,
+        confirmation: &Confirmation,
+    ) -> Result<ConfirmationDetails, Error> {
+        let details = self.with_session(|| self.mobile_api.get_confirmation_details(confirmation)).await?;
+        
+        match details {
+            RawConfirmationDetails::Trade { tradeofferid } => {
+                let offer = self.api.get_offer(tradeofferid).await?;
+                
+                Ok(ConfirmationDetails::Trade(offer))
+            },
+            RawConfirmationDetails::MarketListing { item, classinfo, prices } => {
+                let classinfo = match classinfo {
+                    Some(classinfo) => classinfo,
+                    // the hover data didn't fully describe the item
+                    None => {
+                        let class = (item.appid, item.classid, item.instanceid);
+                        let map = self.api.get_asset_classinfos(&[class]).await?;
+                        
+                        map.get(&class)
+                            .ok_or(MissingClassInfoError {
+                                appid: item.appid,
+                                classid: item.classid,
+                                instanceid: item.instanceid,
+                            })?
+                            .clone()
+                    },
+                };
+                
+                Ok(ConfirmationDetails::MarketListing {
+                    item: response::asset::Asset {
+                        appid: item.appid,
+                        contextid: item.contextid,
+                        assetid: item.assetid,
+                        amount: item.amount,
+                        classinfo,
+                    },
+                    prices,
+                })
+            },
+            RawConfirmationDetails::Other(html) => Ok(ConfirmationDetails::Other(html)),
+        }
+    }
+    
     /// Accepts a confirmation.
     pub async fn accept_confirmation(
         &self,
         confirmation: &Confirmation,
     ) -> Result<(), Error> {
-        self.mobile_api.accept_confirmation(confirmation).await
+        self.with_session(|| self.mobile_api.accept_confirmation(confirmation)).await
     }
     
-    /// Accepts confirmations.
+    /// Accepts confirmations in one request. Returns the result for each confirmation.
     pub async fn accept_confirmations(
         &self,
         confirmations: &[Confirmation],
-    ) -> Result<(), Error> {
-        for confirmation in confirmations {
-            self.mobile_api.accept_confirmation(confirmation).await?
-        }
-        
-        Ok(())
+    ) -> Result<Vec<ConfirmationResult>, Error> {
+        self.with_session(|| self.mobile_api.accept_confirmations(confirmations)).await
     }
     
     /// Declines a confirmation.
@@ -252,7 +484,15 @@ impl TradeOfferManager {
         &self,
         confirmation: &Confirmation,
     ) -> Result<(), Error> {
-        self.mobile_api.deny_confirmation(confirmation).await
+        self.with_session(|| self.mobile_api.decline_confirmation(confirmation)).await
+    }
+    
+    /// Declines confirmations in one request. Returns the result for each confirmation.
+    pub async fn decline_confirmations(
+        &self,
+        confirmations: &[Confirmation],
+    ) -> Result<Vec<ConfirmationResult>, Error> {
+        self.with_session(|| self.mobile_api.decline_confirmations(confirmations)).await
     }
     
     /// Gets the trade receipt (new items) upon completion of a trade.
@@ -262,24 +502,48 @@ impl TradeOfferManager {
         } else if offer.items_to_receive.is_empty() {
             Ok(Vec::new())
         } else if let Some(tradeid) = offer.tradeid {
-            self.api.get_receipt(&tradeid).await
+            self.with_session(|| self.api.get_receipt(&tradeid)).await
         } else {
             Err(Error::Parameter("Missing tradeid"))
         }
     }
     
-    /// Updates the offer to the most recent state against the API.
-    pub async fn update_offer(&self, offer: &mut response::trade_offer::TradeOffer) -> Result<(), Error> {
-        let updated = self.api.get_trade_offer(offer.tradeofferid).await?;
+    /// Gets the status of a trade along with the new IDs of its items.
+    pub async fn get_trade_status(
+        &self,
+        tradeid: TradeId,
+    ) -> Result<response::trade::Trade, Error> {
+        self.api.get_trade_status(tradeid).await
+    }
+    
+    /// Gets counts of pending, new and updated offers.
+    pub async fn get_trade_offers_summary(
+        &self,
+    ) -> Result<response::trade_offers_summary::TradeOffersSummary, Error> {
+        let last_poll = self.poller.poll_data.read().unwrap().last_poll;
         
-        offer.tradeofferid = updated.tradeofferid;
-        offer.tradeid = updated.tradeid;
-        offer.trade_offer_state = updated.trade_offer_state;
-        offer.confirmation_method = updated.confirmation_method;
-        offer.escrow_end_date = updated.escrow_end_date;
-        offer.time_created = updated.time_created;
-        offer.time_updated = updated.time_updated;
-        offer.expiration_time = updated.expiration_time;
+        self.api.get_trade_offers_summary(last_poll).await
+    }
+    
+    /// Gets a page of trade history.
+    pub async fn get_trade_history(
+        &self,
+        options: &request::trade_history::TradeHistoryOptions,
+    ) -> Result<response::trade_history::TradeHistory, Error> {
+        self.api.get_trade_history(options).await
+    }
+    
+    /// Gets an offer with its items.
+    pub async fn get_offer(
+        &self,
+        tradeofferid: TradeOfferId,
+    ) -> Result<response::trade_offer::TradeOffer, Error> {
+        self.api.get_offer(tradeofferid).await
+    }
+    
+    /// Updates the offer to the most recent state against the API, including its items.
+    pub async fn update_offer(&self, offer: &mut response::trade_offer::TradeOffer) -> Result<(), Error> {
+        *offer = self.api.get_offer(offer.tradeofferid).await?;
         
         Ok(())
     }
@@ -305,137 +569,16 @@ impl TradeOfferManager {
         &self,
         full_update: bool
     ) -> Result<Poll, Error> {
-        fn date_difference_from_now(date: &ServerTime) -> i64 {
-            let current_timestamp = time::get_server_time_now().timestamp();
-            
-            current_timestamp - date.timestamp()
-        }
-        
-        fn last_poll_full_outdated(last_poll_full_update: Option<ServerTime>) -> bool {
-            if let Some(last_poll_full_update) = last_poll_full_update {
-                date_difference_from_now(&last_poll_full_update) >= 5 * 60
-            } else {
-                true
-            }
-        }
-        
-        let mut offers_since = 0;
-        let mut filter = OfferFilter::ActiveOnly;
-        
-        {
-            let mut poll_data = self.poll_data.write().unwrap();
-
-            if let Some(last_poll) = poll_data.last_poll {
-                let seconds_since_last_poll = date_difference_from_now(&last_poll);
-                    
-                if seconds_since_last_poll <= 1 {
-                    // We last polled less than a second ago... we shouldn't spam the API
-                    return Err(Error::PollCalledTooSoon);
-                }            
-            }
-            
-            poll_data.last_poll = Some(time::get_server_time_now());
-        
-            if full_update || last_poll_full_outdated(poll_data.last_poll_full_update) {
-                filter = OfferFilter::All;
-                offers_since = 1;
-                poll_data.last_poll_full_update = Some(time::get_server_time_now())
-            } else if let Some(poll_offers_since) = poll_data.offers_since {
-                // It looks like sometimes Steam can be dumb and backdate a modified offer. We need to handle this.
-                // Let's add a 30-minute buffer.
-                offers_since = poll_offers_since.timestamp() - 1800;
-            }
-        }
-
-        let historical_cutoff = time::timestamp_to_server_time(offers_since);
-        let mut offers = self.api.get_trade_offers(&filter, &Some(historical_cutoff)).await?;
-        let mut offers_since: i64 = 0;
-        let mut poll: Poll = Vec::new();
-        
-        if let Some(cancel_duration) = self.cancel_duration {
-            let cancel_time = chrono::Utc::now() - cancel_duration;
-            let offers_to_cancel = offers
-                .iter_mut()
-                .filter(|offer| {
-                    let is_active_state = {
-                        offer.trade_offer_state == TradeOfferState::Active ||
-                        offer.trade_offer_state == TradeOfferState::CreatedNeedsConfirmation
-                    };
-                    
-                    is_active_state &&
-                    offer.is_our_offer &&
-                    offer.time_created < cancel_time
-                });
-            let cancel_futures = offers_to_cancel
-                .map(|offer| async { self.cancel_offer(offer).await })
-                .collect::<Vec<_>>();
-            
-            // cancels all offers older than cancel_time
-            // this will also update the state for the offers that were cancelled
-            futures::future::join_all(cancel_futures).await;
-        }
-        
-        {
-            let mut poll_data = self.poll_data.write().unwrap();
-                
-            for offer in offers {
-                offers_since = cmp::max(offers_since, offer.time_updated.timestamp());
-
-                match poll_data.state_map.get(&offer.tradeofferid) {
-                    Some(poll_trade_offer_state) => {
-                        if poll_trade_offer_state != &offer.trade_offer_state {
-                            let tradeofferid = offer.tradeofferid;
-                            let new_state = offer.trade_offer_state.clone();
-                            
-                            poll.push((offer, Some(poll_trade_offer_state.clone())));
-                            
-                            poll_data.state_map.insert(tradeofferid, new_state);
-                        }
-                    },
-                    None => {
-                        poll_data.state_map.insert(offer.tradeofferid, offer.trade_offer_state.clone());
-                        
-                        poll.push((offer, None));
-                    },
-                }
-            }
-            
-            // Clear poll data offers otherwise this could expand infinitely.
-            // Using a higher number than is removed so this process needs to run less frequently.
-            if poll_data.state_map.len() > 2500 {
-                let mut tradeofferids = poll_data.state_map
-                    .keys()
-                    .cloned()
-                    .collect::<Vec<_>>();
-                
-                // high to low
-                tradeofferids.sort_by(|a, b| b.cmp(a));
-                
-                let (
-                    _tradeofferids,
-                    tradeofferids_to_remove,
-                ) = tradeofferids.split_at(2000);
-                
-                for tradeofferid in tradeofferids_to_remove {
-                    poll_data.state_map.remove(tradeofferid);
-                }
-            }
-            
-            if offers_since > 0 {
-                poll_data.offers_since = Some(time::timestamp_to_server_time(offers_since));
-            }
-        }
-        
-        let _ = self.save_poll_data().await;
-        
-        Ok(poll)
+        self.poller.do_poll(full_update).await
     }
     
-    async fn save_poll_data(&self) -> Result<(), FileError> {
-        // we clone this so we don't hold it across an await
-        let poll_data = self.poll_data.read().unwrap().clone();
-        let data = serde_json::to_string(&poll_data)?;
-        
-        file::save_poll_data(&self.steamid, &data).await
+    /// Starts polling for changes to offers in the background on the given interval. Events are
+    /// delivered through the returned stream, and polling stops when the stream is closed or 
+    /// dropped.
+    pub fn start_polling(
+        &self,
+        interval: std::time::Duration,
+    ) -> PollStream {
+        Arc::clone(&self.poller).start(interval)
     }
 }
\ No newline at end of file
@@ -1,10 +1,163 @@
-use crate::{response, enums::TradeOfferState};
+use crate::{response, enums::TradeOfferState, error::Error};
+use tokio::sync::mpsc;
+use futures::Stream;
+use std::{pin::Pin, task::{Context, Poll as TaskPoll}};
 
 pub type Poll = Vec<(response::trade_offer::TradeOffer, Option<TradeOfferState>)>;
 
+/// A change in state for an offer we were already tracking.
 #[derive(Debug)]
 pub struct PollChange {
     pub old_state: TradeOfferState,
     pub new_state: TradeOfferState,
     pub offer: response::trade_offer::TradeOffer,
-}
\ No newline at end of file
+}
+
+/// An event emitted from [`PollStream`].
+#[derive(Debug)]
+pub enum PollEvent {
+    /// An active offer was received that we have not seen before.
+    NewReceivedOffer(response::trade_offer::TradeOffer),
+    /// An active offer was sent that we have not seen before e.g. an offer sent from the website.
+    NewSentOffer(response::trade_offer::TradeOffer),
+    /// The state of an offer we received changed.
+    ReceivedOfferChanged(PollChange),
+    /// The state of an offer we sent changed.
+    SentOfferChanged(PollChange),
+    /// An offer we sent was canceled for being older than the manager's cancel duration.
+    OfferCanceledByTimeout(response::trade_offer::TradeOffer),
+    /// An error occurred during a poll. Polling will continue on the next interval.
+    PollError(Error),
+}
+
+impl PollEvent {
+    /// Classifies an offer from a poll. Returns `None` if the offer is not worth reporting e.g.
+    /// an offer seen for the first time that is no longer active.
+    pub(crate) fn classify(
+        offer: response::trade_offer::TradeOffer,
+        old_state: Option<TradeOfferState>,
+        canceled_by_timeout: bool,
+    ) -> Option<Self> {
+        if canceled_by_timeout {
+            return Some(Self::OfferCanceledByTimeout(offer));
+        }
+        
+        match old_state {
+            Some(old_state) => {
+                let change = PollChange {
+                    old_state,
+                    new_state: offer.trade_offer_state.clone(),
+                    offer,
+                };
+                
+                if change.offer.is_our_offer {
+                    Some(Self::SentOfferChanged(change))
+                } else {
+                    Some(Self::ReceivedOfferChanged(change))
+                }
+            },
+            None if offer.trade_offer_state != TradeOfferState::Active => None,
+            None if offer.is_our_offer => Some(Self::NewSentOffer(offer)),
+            None => Some(Self::NewReceivedOffer(offer)),
+        }
+    }
+}
+
+/// A stream of [`PollEvent`] values produced by a background poll loop. The loop stops when this
+/// stream is dropped. To shut down without losing events, call [`PollStream::close`] and
+/// continue reading until the stream ends.
+#[derive(Debug)]
+pub struct PollStream {
+    receiver: mpsc::Receiver<PollEvent>,
+}
+
+impl PollStream {
+    pub(crate) fn new(receiver: mpsc::Receiver<PollEvent>) -> Self {
+        Self {
+            receiver,
+        }
+    }
+    
+    /// Stops the poll loop. A poll in progress is allowed to complete and any events already
+    /// produced can still be read from the stream.
+    pub fn close(&mut self) {
+        self.receiver.close();
+    }
+}
+
+impl Stream for PollStream {
+    type Item = PollEvent;
+    
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> TaskPoll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SteamID, enums::ConfirmationMethod, time};
+    
+    fn offer(is_our_offer: bool, trade_offer_state: TradeOfferState) -> response::trade_offer::TradeOffer {
+        let now = time::get_system_time_now();
+        
+        response::trade_offer::TradeOffer {
+            tradeofferid: 1,
+            tradeid: None,
+            partner: SteamID::from(76561198080179568),
+            message: None,
+            items_to_receive: Vec::new(),
+            items_to_give: Vec::new(),
+            currency_to_receive: Vec::new(),
+            currency_to_give: Vec::new(),
+            is_our_offer,
+            from_real_time_trade: false,
+            expiration_time: now,
+            time_created: now,
+            time_updated: now,
+            trade_offer_state,
+            escrow_end_date: now,
+            confirmation_method: ConfirmationMethod::None,
+        }
+    }
+    
+    #[test]
+    fn classifies_new_offers() {
+        let event = PollEvent::classify(offer(false, TradeOfferState::Active), None, false);
+        
+        assert!(matches!(event, Some(PollEvent::NewReceivedOffer(_))));
+        
+        let event = PollEvent::classify(offer(true, TradeOfferState::Active), None, false);
+        
+        assert!(matches!(event, Some(PollEvent::NewSentOffer(_))));
+        
+        let event = PollEvent::classify(offer(false, TradeOfferState::Declined), None, false);
+        
+        assert!(event.is_none());
+    }
+    
+    #[test]
+    fn classifies_changed_offers() {
+        let event = PollEvent::classify(
+            offer(true, TradeOfferState::Accepted),
+            Some(TradeOfferState::Active),
+            false,
+        );
+        
+        match event {
+            Some(PollEvent::SentOfferChanged(change)) => {
+                assert_eq!(change.old_state, TradeOfferState::Active);
+                assert_eq!(change.new_state, TradeOfferState::Accepted);
+            },
+            other => panic!("Unexpected event {:?}", other),
+        }
+        
+        let event = PollEvent::classify(
+            offer(true, TradeOfferState::Canceled),
+            Some(TradeOfferState::Active),
+            true,
+        );
+        
+        assert!(matches!(event, Some(PollEvent::OfferCanceledByTimeout(_))));
+    }
+}
@@ -6,7 +6,8 @@ use crate::{
     enums::TradeOfferState,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Data used to track the state of offers between polls.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PollData {
     pub offers_since: Option<ServerTime>,
     pub last_poll: Option<ServerTime>,
@@ -0,0 +1,183 @@
+use super::poll_data::PollData;
+use crate::{error::FileError, helpers::write_file_atomic};
+use fs2::FileExt;
+use std::{
+    fmt,
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+};
+
+/// Storage for [`PollData`]. Poll data is loaded once when the manager is built and saved after
+/// every poll. Both methods may block - the poll loop saves from a blocking thread.
+pub trait PollDataStore: fmt::Debug + Send + Sync {
+    /// Loads the stored poll data. Returns `None` if nothing has been stored yet.
+    fn load(&self) -> Result<Option<PollData>, FileError>;
+    
+    /// Saves the poll data.
+    fn save(&self, poll_data: &PollData) -> Result<(), FileError>;
+}
+
+/// Keeps poll data in memory. Poll data is lost when the program exits.
+#[derive(Debug, Default)]
+pub struct InMemoryPollDataStore {
+    poll_data: Mutex<Option<PollData>>,
+}
+
+impl InMemoryPollDataStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PollDataStore for InMemoryPollDataStore {
+    fn load(&self) -> Result<Option<PollData>, FileError> {
+        Ok(self.poll_data.lock().unwrap().clone())
+    }
+    
+    fn save(&self, poll_data: &PollData) -> Result<(), FileError> {
+        *self.poll_data.lock().unwrap() = Some(poll_data.clone());
+        
+        Ok(())
+    }
+}
+
+/// Stores poll data as JSON in a file. Writes are atomic. An advisory lock is taken on a
+/// `.lock` file next to the data file so that only one process can write poll data for the same
+/// file at a time. The lock is held for as long as the store exists.
+#[derive(Debug)]
+pub struct FilePollDataStore {
+    filepath: PathBuf,
+    lock: Mutex<Option<fs::File>>,
+}
+
+impl FilePollDataStore {
+    /// Creates a new store at the given file path. Parent directories are created as needed.
+    pub fn new<P: Into<PathBuf>>(filepath: P) -> Self {
+        Self {
+            filepath: filepath.into(),
+            lock: Mutex::new(None),
+        }
+    }
+    
+    /// The path to the data file.
+    pub fn filepath(&self) -> &PathBuf {
+        &self.filepath
+    }
+    
+    fn lock_filepath(&self) -> PathBuf {
+        let mut filepath = self.filepath.clone().into_os_string();
+        
+        filepath.push(".lock");
+        filepath.into()
+    }
+    
+    /// Acquires the lock for this file if it is not already held.
+    fn acquire_lock(&self) -> Result<(), FileError> {
+        let mut lock = self.lock.lock().unwrap();
+        
+        if lock.is_some() {
+            return Ok(());
+        }
+        
+        if let Some(parent) = self.filepath.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.lock_filepath())?;
+        
+        if file.try_lock_exclusive().is_err() {
+            return Err(FileError::Locked);
+        }
+        
+        *lock = Some(file);
+        
+        Ok(())
+    }
+}
+
+impl PollDataStore for FilePollDataStore {
+    fn load(&self) -> Result<Option<PollData>, FileError> {
+        self.acquire_lock()?;
+        
+        match fs::read_to_string(&self.filepath) {
+            Ok(data) => Ok(Some(serde_json::from_str(&data)?)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+    
+    fn save(&self, poll_data: &PollData) -> Result<(), FileError> {
+        self.acquire_lock()?;
+        
+        let data = serde_json::to_string(poll_data)?;
+        
+        write_file_atomic(&self.filepath, data.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::TradeOfferState;
+    
+    fn get_filepath(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("steam_tradeoffers_test_{}", std::process::id()))
+            .join(name)
+    }
+    
+    #[test]
+    fn file_store_saves_and_loads() {
+        let filepath = get_filepath("poll_data_saves.json");
+        let store = FilePollDataStore::new(&filepath);
+        let mut poll_data = PollData::new();
+        
+        poll_data.state_map.insert(1, TradeOfferState::Active);
+        
+        assert!(store.load().unwrap().is_none());
+        
+        store.save(&poll_data).unwrap();
+        
+        let loaded = store.load().unwrap().unwrap();
+        
+        assert_eq!(loaded.state_map.get(&1), Some(&TradeOfferState::Active));
+        
+        let _ = fs::remove_file(filepath);
+    }
+    
+    #[test]
+    fn file_store_is_locked_by_other_store() {
+        let filepath = get_filepath("poll_data_locked.json");
+        let store = FilePollDataStore::new(&filepath);
+        let other_store = FilePollDataStore::new(&filepath);
+        
+        store.load().unwrap();
+        
+        assert!(matches!(other_store.load(), Err(FileError::Locked)));
+        assert!(matches!(other_store.save(&PollData::new()), Err(FileError::Locked)));
+        
+        drop(store);
+        
+        assert!(other_store.load().is_ok());
+    }
+    
+    #[test]
+    fn manager_is_not_built_when_poll_data_is_locked() {
+        let filepath = get_filepath("poll_data_manager_locked.json");
+        let store = FilePollDataStore::new(&filepath);
+        
+        store.load().unwrap();
+        
+        let result = crate::TradeOfferManager::builder(crate::SteamID::from(76561198080179568), String::new())
+            .poll_data_path(&filepath)
+            .try_build();
+        
+        assert!(matches!(result, Err(FileError::Locked)));
+    }
+}
@@ -0,0 +1,299 @@
+use super::{
+    poll_data::PollData,
+    poll_data_store::PollDataStore,
+    session::SessionRefresher,
+    poll::{Poll, PollEvent, PollStream},
+};
+use std::{cmp, collections::HashSet, sync::{Arc, RwLock, Mutex}};
+use chrono::Duration;
+use tokio::sync::mpsc;
+use log::warn;
+use crate::{
+    ServerTime,
+    time,
+    api::SteamTradeOfferAPI,
+    mobile_api::TimeSync,
+    enums::{OfferFilter, TradeOfferState},
+    error::{Error, FileError},
+    response::TradeOffersSummary,
+    types::TradeOfferId,
+};
+
+/// Holds the state needed to poll for offer changes. Shared between the manager and any
+/// background poll loops.
+#[derive(Debug)]
+pub struct Poller {
+    pub api: Arc<SteamTradeOfferAPI>,
+    /// Refreshes the session for requests that need one e.g. canceling offers.
+    pub session: Arc<SessionRefresher>,
+    /// The offset between Steam's clock and the system clock, shared with the mobile API.
+    pub time_sync: Arc<TimeSync>,
+    pub poll_data: Arc<RwLock<PollData>>,
+    pub poll_data_store: Arc<dyn PollDataStore>,
+    pub cancel_duration: Option<Duration>,
+    /// Whether the poll loop checks the offers summary before getting offers.
+    pub poll_with_summary: bool,
+    /// The summary from the last successful poll in the poll loop.
+    pub last_summary: Mutex<Option<TradeOffersSummary>>,
+}
+
+fn date_difference_from_now(date: &ServerTime, now: &ServerTime) -> i64 {
+    now.timestamp() - date.timestamp()
+}
+
+fn last_poll_full_outdated(last_poll_full_update: Option<ServerTime>, now: &ServerTime) -> bool {
+    if let Some(last_poll_full_update) = last_poll_full_update {
+        date_difference_from_now(&last_poll_full_update, now) >= 5 * 60
+    } else {
+        true
+    }
+}
+
+impl Poller {
+    /// Spawns a task which polls on the given interval, sending events to the returned stream.
+    pub fn start(
+        self: Arc<Self>,
+        interval: std::time::Duration,
+    ) -> PollStream {
+        let (sender, receiver) = mpsc::channel(100);
+        
+        tokio::spawn(async move {
+            loop {
+                let events = self.poll_events().await;
+                
+                for event in events {
+                    if sender.send(event).await.is_err() {
+                        // the stream was closed or dropped
+                        return;
+                    }
+                }
+                
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = sender.closed() => return,
+                }
+            }
+        });
+        
+        PollStream::new(receiver)
+    }
+    
+    /// Polls for changes to offers from the poll loop, skipping the poll if the summary shows
+    /// nothing has changed.
+    async fn poll_events(&self) -> Vec<PollEvent> {
+        let summary = if self.poll_with_summary {
+            match self.get_summary_if_changed().await {
+                Ok(Some(summary)) => Some(summary),
+                // nothing changed
+                Ok(None) => return Vec::new(),
+                Err(error) => {
+                    warn!("Error getting trade offers summary: {}", error);
+                    None
+                },
+            }
+        } else {
+            None
+        };
+        
+        match self.poll(false).await {
+            Ok((poll, canceled)) => {
+                // only stored once the poll succeeds so changes aren't missed if it fails
+                if let Some(summary) = summary {
+                    *self.last_summary.lock().unwrap() = Some(summary);
+                }
+                
+                poll
+                    .into_iter()
+                    .filter_map(|(offer, old_state)| {
+                        let canceled_by_timeout = canceled.contains(&offer.tradeofferid);
+                        
+                        PollEvent::classify(offer, old_state, canceled_by_timeout)
+                    })
+                    .collect()
+            },
+            Err(error) => vec![PollEvent::PollError(error)],
+        }
+    }
+    
+    /// Gets the offers summary. Returns `None` if nothing has changed since the last summary and
+    /// a poll isn't otherwise needed.
+    async fn get_summary_if_changed(&self) -> Result<Option<TradeOffersSummary>, Error> {
+        let (last_poll, full_update_outdated) = {
+            let poll_data = self.poll_data.read().unwrap();
+            
+            (poll_data.last_poll, last_poll_full_outdated(poll_data.last_poll_full_update, &self.time_sync.server_time_now()))
+        };
+        let summary = self.api.get_trade_offers_summary(last_poll).await?;
+        let unchanged = self.last_summary
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|previous| !summary.has_changes_since(previous));
+        // pending sent offers may need to be canceled
+        let needs_cancel_check = self.cancel_duration.is_some() && summary.pending_sent_count > 0;
+        
+        if unchanged && !full_update_outdated && !needs_cancel_check {
+            Ok(None)
+        } else {
+            Ok(Some(summary))
+        }
+    }
+    
+    /// Performs a poll for changes to offers.
+    pub async fn do_poll(
+        &self,
+        full_update: bool,
+    ) -> Result<Poll, Error> {
+        let (poll, _canceled) = self.poll(full_update).await?;
+        
+        Ok(poll)
+    }
+    
+    /// Performs a poll for changes to offers. Also returns the IDs of offers that were canceled
+    /// for exceeding the cancel duration.
+    async fn poll(
+        &self,
+        full_update: bool,
+    ) -> Result<(Poll, HashSet<TradeOfferId>), Error> {
+        let mut offers_since = 0;
+        let mut filter = OfferFilter::ActiveOnly;
+        
+        {
+            let mut poll_data = self.poll_data.write().unwrap();
+            let now = self.time_sync.server_time_now();
+            
+            if let Some(last_poll) = poll_data.last_poll {
+                let seconds_since_last_poll = date_difference_from_now(&last_poll, &now);
+                
+                if seconds_since_last_poll <= 1 {
+                    // We last polled less than a second ago... we shouldn't spam the API
+                    return Err(Error::PollCalledTooSoon);
+                }
+            }
+            
+            poll_data.last_poll = Some(now);
+            
+            if full_update || last_poll_full_outdated(poll_data.last_poll_full_update, &now) {
+                filter = OfferFilter::All;
+                offers_since = 1;
+                poll_data.last_poll_full_update = Some(now)
+            } else if let Some(poll_offers_since) = poll_data.offers_since {
+                // It looks like sometimes Steam can be dumb and backdate a modified offer. We need to handle this.
+                // Let's add a 30-minute buffer.
+                offers_since = poll_offers_since.timestamp() - 1800;
+            }
+        }
+        
+        let historical_cutoff = time::timestamp_to_server_time(offers_since);
+        let mut offers = self.api.get_trade_offers(&filter, &Some(historical_cutoff)).await?;
+        let mut offers_since: i64 = 0;
+        let mut poll: Poll = Vec::new();
+        let mut canceled: HashSet<TradeOfferId> = HashSet::new();
+        
+        if let Some(cancel_duration) = self.cancel_duration {
+            let cancel_time = self.time_sync.server_time_now() - cancel_duration;
+            let offers_to_cancel = offers
+                .iter_mut()
+                .filter(|offer| {
+                    let is_active_state = {
+                        offer.trade_offer_state == TradeOfferState::Active ||
+                        offer.trade_offer_state == TradeOfferState::CreatedNeedsConfirmation
+                    };
+                    
+                    is_active_state &&
+                    offer.is_our_offer &&
+                    offer.time_created < cancel_time
+                });
+            let cancel_futures = offers_to_cancel
+                .map(|offer| async {
+                    let tradeofferid = offer.tradeofferid;
+                    
+                    self.session.with_session(|| self.api.cancel_offer(tradeofferid)).await?;
+                    offer.trade_offer_state = TradeOfferState::Canceled;
+                    
+                    Ok::<_, Error>(offer.tradeofferid)
+                })
+                .collect::<Vec<_>>();
+            
+            // cancels all offers older than cancel_time
+            // this will also update the state for the offers that were cancelled
+            canceled = futures::future::join_all(cancel_futures).await
+                .into_iter()
+                .filter_map(|result| match result {
+                    Ok(tradeofferid) => Some(tradeofferid),
+                    Err(error) => {
+                        warn!("Error canceling offer: {}", error);
+                        None
+                    },
+                })
+                .collect();
+        }
+        
+        {
+            let mut poll_data = self.poll_data.write().unwrap();
+            
+            for offer in offers {
+                offers_since = cmp::max(offers_since, offer.time_updated.timestamp());
+                
+                match poll_data.state_map.get(&offer.tradeofferid) {
+                    Some(poll_trade_offer_state) => {
+                        if poll_trade_offer_state != &offer.trade_offer_state {
+                            let tradeofferid = offer.tradeofferid;
+                            let new_state = offer.trade_offer_state.clone();
+                            
+                            poll.push((offer, Some(poll_trade_offer_state.clone())));
+                            
+                            poll_data.state_map.insert(tradeofferid, new_state);
+                        }
+                    },
+                    None => {
+                        poll_data.state_map.insert(offer.tradeofferid, offer.trade_offer_state.clone());
+                        
+                        poll.push((offer, None));
+                    },
+                }
+            }
+            
+            // Clear poll data offers otherwise this could expand infinitely.
+            // Using a higher number than is removed so this process needs to run less frequently.
+            if poll_data.state_map.len() > 2500 {
+                let mut tradeofferids = poll_data.state_map
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                
+                // high to low
+                tradeofferids.sort_by(|a, b| b.cmp(a));
+                
+                let (
+                    _tradeofferids,
+                    tradeofferids_to_remove,
+                ) = tradeofferids.split_at(2000);
+                
+                for tradeofferid in tradeofferids_to_remove {
+                    poll_data.state_map.remove(tradeofferid);
+                }
+            }
+            
+            if offers_since > 0 {
+                poll_data.offers_since = Some(time::timestamp_to_server_time(offers_since));
+            }
+        }
+        
+        if let Err(error) = self.save_poll_data().await {
+            warn!("Error saving poll data: {}", error);
+        }
+        
+        Ok((poll, canceled))
+    }
+    
+    async fn save_poll_data(&self) -> Result<(), FileError> {
+        // we clone this so we don't hold it across an await
+        let poll_data = self.poll_data.read().unwrap().clone();
+        let poll_data_store = Arc::clone(&self.poll_data_store);
+        
+        tokio::task::spawn_blocking(move || poll_data_store.save(&poll_data))
+            .await
+            .map_err(|_| FileError::JoinError)?
+    }
+}
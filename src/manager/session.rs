@@ -0,0 +1,186 @@
+use crate::error::Error;
+use async_trait::async_trait;
+use std::{
+    fmt,
+    future::Future,
+    sync::{Arc, atomic::{AtomicU64, Ordering}},
+};
+
+/// A web session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub sessionid: String,
+    pub cookies: Vec<String>,
+}
+
+/// Provides new sessions when the current session expires. The manager calls this when a
+/// request fails with [`Error::NotLoggedIn`], applies the new session, then retries the
+/// request once.
+#[async_trait]
+pub trait SessionProvider: fmt::Debug + Send + Sync {
+    /// Gets a new session.
+    async fn get_session(&self) -> Result<Session, Error>;
+}
+
+/// Applies a new session to the clients that use it.
+type ApplySession = Box<dyn Fn(&Session) -> Result<(), url::ParseError> + Send + Sync>;
+
+/// Refreshes the session using a [`SessionProvider`]. Concurrent refreshes are coalesced into
+/// one. Shared by the manager and the poller.
+pub struct SessionRefresher {
+    provider: Option<Arc<dyn SessionProvider>>,
+    apply: ApplySession,
+    /// Incremented each time the session is refreshed.
+    generation: AtomicU64,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl fmt::Debug for SessionRefresher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionRefresher")
+            .field("provider", &self.provider)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+impl SessionRefresher {
+    /// Creates a new refresher. `apply` sets a new session on the clients that use it.
+    pub fn new<F>(provider: Option<Arc<dyn SessionProvider>>, apply: F) -> Self
+    where
+        F: Fn(&Session) -> Result<(), url::ParseError> + Send + Sync + 'static,
+    {
+        Self {
+            provider,
+            apply: Box::new(apply),
+            generation: AtomicU64::new(0),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+    
+    /// Calls `f`, then if the session has expired and a provider is set, refreshes the session 
+    /// and calls `f` once more.
+    pub async fn with_session<T, F, Fut>(&self, f: F) -> Result<T, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let generation = self.generation();
+        
+        match f().await {
+            Err(Error::NotLoggedIn) if self.has_provider() => {
+                self.refresh(generation).await?;
+                
+                f().await
+            },
+            result => result,
+        }
+    }
+    
+    /// Whether a provider is set.
+    pub fn has_provider(&self) -> bool {
+        self.provider.is_some()
+    }
+    
+    /// The current generation of the session. Read this before making a request and pass it to
+    /// [`SessionRefresher::refresh`] if the request fails.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+    
+    /// Gets a new session and applies it, unless the session was already refreshed since
+    /// `generation`.
+    pub async fn refresh(
+        &self,
+        generation: u64,
+    ) -> Result<(), Error> {
+        let provider = match &self.provider {
+            Some(provider) => provider,
+            None => return Err(Error::NotLoggedIn),
+        };
+        let _lock = self.lock.lock().await;
+        
+        if self.generation() != generation {
+            // another request refreshed the session while we were waiting
+            return Ok(());
+        }
+        
+        let session = provider.get_session().await?;
+        
+        (self.apply)(&session)?;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    
+    #[derive(Debug, Default)]
+    struct CountingSessionProvider {
+        calls: AtomicUsize,
+    }
+    
+    #[async_trait]
+    impl SessionProvider for CountingSessionProvider {
+        async fn get_session(&self) -> Result<Session, Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            
+            Ok(Session {
+                sessionid: String::from("sessionid"),
+                cookies: Vec::new(),
+            })
+        }
+    }
+    
+    #[tokio::test]
+    async fn coalesces_concurrent_refreshes() {
+        let provider = Arc::new(CountingSessionProvider::default());
+        let refresher = SessionRefresher::new(Some(provider.clone()), |_| Ok(()));
+        let generation = refresher.generation();
+        let (a, b) = tokio::join!(
+            refresher.refresh(generation),
+            refresher.refresh(generation),
+        );
+        
+        assert!(a.is_ok() && b.is_ok());
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(refresher.generation(), generation + 1);
+    }
+    
+    #[tokio::test]
+    async fn with_session_refreshes_and_retries_once() {
+        let applied = Arc::new(AtomicUsize::new(0));
+        let refresher = SessionRefresher::new(Some(Arc::new(CountingSessionProvider::default())), {
+            let applied = Arc::clone(&applied);
+            
+            move |_| {
+                applied.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+        let calls = AtomicUsize::new(0);
+        let result = refresher.with_session(|| async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(Error::NotLoggedIn),
+                _ => Ok(()),
+            }
+        }).await;
+        
+        assert!(result.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(applied.load(Ordering::SeqCst), 1);
+    }
+    
+    #[tokio::test]
+    async fn refresh_without_provider_is_not_logged_in() {
+        let refresher = SessionRefresher::new(None, |_| Ok(()));
+        let result = refresher.refresh(refresher.generation()).await;
+        
+        assert!(matches!(result, Err(Error::NotLoggedIn)));
+    }
+}
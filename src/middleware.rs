@@ -0,0 +1,252 @@
+//! Middleware for rate limiting requests and retrying failed requests.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use reqwest::{Method, Request, Response, StatusCode, header::RETRY_AFTER};
+use reqwest_middleware::{Middleware, Next};
+use task_local_extensions::Extensions;
+use rand::Rng;
+use log::debug;
+
+/// A limit on the number of requests made within a period of time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// The number of requests allowed within the period.
+    pub requests: u32,
+    /// The period.
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, per: Duration) -> Self {
+        Self {
+            requests,
+            per,
+        }
+    }
+    
+    /// The time between each request.
+    fn interval(&self) -> Duration {
+        self.per / self.requests.max(1)
+    }
+}
+
+/// Limits requests per host. Requests to a host are spaced out evenly according to the host's
+/// [`RateLimit`]. Wrap this in an [`Arc`] and give it to each manager to keep several managers
+/// under the same limits.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    hosts: HashMap<String, RateLimit>,
+    default_limit: Option<RateLimit>,
+    next_request: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter with no limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Sets the limit for a host e.g. `"steamcommunity.com"` or `"api.steampowered.com"`.
+    pub fn host(mut self, host: &str, limit: RateLimit) -> Self {
+        self.hosts.insert(host.to_string(), limit);
+        self
+    }
+    
+    /// Sets the limit for hosts without their own limit. Requests to these hosts are not limited
+    /// by default.
+    pub fn default_limit(mut self, limit: RateLimit) -> Self {
+        self.default_limit = Some(limit);
+        self
+    }
+    
+    /// Waits until a request can be made to the host.
+    pub async fn wait(&self, host: &str) {
+        if let Some(delay) = self.reserve(host, Instant::now()) {
+            tokio::time::sleep(delay).await;
+        }
+    }
+    
+    /// Reserves the next slot for a request to the host. Returns how long to wait until the
+    /// slot, if the host is limited.
+    fn reserve(&self, host: &str, now: Instant) -> Option<Duration> {
+        let limit = self.hosts.get(host).or(self.default_limit.as_ref())?;
+        let mut next_request = self.next_request.lock().unwrap();
+        let next = next_request.entry(host.to_string()).or_insert(now);
+        let slot = (*next).max(now);
+        
+        *next = slot + limit.interval();
+        
+        Some(slot - now)
+    }
+}
+
+/// How failed GET requests are retried. Requests are retried on timeouts, connection errors,
+/// `429 Too Many Requests` and `5xx` responses, waiting an exponentially increasing delay with
+/// jitter between each attempt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of retries. `0` disables retrying.
+    pub max_retries: u32,
+    /// The delay before the first retry.
+    pub min_delay: Duration,
+    /// The maximum delay between retries.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            min_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+    
+    /// The delay before a retry. Somewhere between half and the whole of the backoff for the
+    /// attempt, and no less than the server asked for with `Retry-After`.
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let backoff = self.min_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let half = backoff / 2;
+        let delay = half + rand::thread_rng().gen_range(Duration::ZERO..=half);
+        
+        match retry_after {
+            Some(retry_after) => delay.max(retry_after.min(self.max_delay)),
+            None => delay,
+        }
+    }
+}
+
+/// Options for the middleware used by the HTTP clients.
+#[derive(Debug, Clone, Default)]
+pub struct MiddlewareOptions {
+    /// Limits requests per host. Requests are not limited by default.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// How failed GET requests are retried.
+    pub retry_policy: RetryPolicy,
+}
+
+pub struct RateLimitMiddleware(pub Arc<RateLimiter>);
+
+#[async_trait::async_trait]
+impl Middleware for RateLimitMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if let Some(host) = req.url().host_str() {
+            self.0.wait(host).await;
+        }
+        
+        next.run(req, extensions).await
+    }
+}
+
+pub struct RetryMiddleware(pub RetryPolicy);
+
+#[async_trait::async_trait]
+impl Middleware for RetryMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        // only idempotent requests are safe to retry
+        if self.0.max_retries == 0 || !matches!(*req.method(), Method::GET | Method::HEAD) {
+            return next.run(req, extensions).await;
+        }
+        
+        let mut attempt = 0;
+        
+        loop {
+            let duplicate = match req.try_clone() {
+                Some(duplicate) => duplicate,
+                // the body is a stream
+                None => return next.run(req, extensions).await,
+            };
+            let result = next.clone().run(duplicate, extensions).await;
+            
+            if attempt >= self.0.max_retries {
+                return result;
+            }
+            
+            let delay = match &result {
+                Ok(response) if is_retryable_status(response.status()) => {
+                    self.0.delay(attempt, get_retry_after(response))
+                },
+                Err(reqwest_middleware::Error::Reqwest(error)) if error.is_timeout() || error.is_connect() => {
+                    self.0.delay(attempt, None)
+                },
+                _ => return result,
+            };
+            
+            debug!("Retrying {} in {}ms", req.url(), delay.as_millis());
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn get_retry_after(response: &Response) -> Option<Duration> {
+    response.headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn spaces_requests_per_host() {
+        let rate_limiter = RateLimiter::new()
+            .host("steamcommunity.com", RateLimit::new(2, Duration::from_secs(1)));
+        let now = Instant::now();
+        
+        assert_eq!(rate_limiter.reserve("steamcommunity.com", now), Some(Duration::ZERO));
+        assert_eq!(rate_limiter.reserve("steamcommunity.com", now), Some(Duration::from_millis(500)));
+        assert_eq!(rate_limiter.reserve("steamcommunity.com", now), Some(Duration::from_millis(1000)));
+        assert_eq!(rate_limiter.reserve("api.steampowered.com", now), None);
+    }
+    
+    #[test]
+    fn backs_off_exponentially() {
+        let policy = RetryPolicy::default();
+        
+        for attempt in 0..3 {
+            let backoff = policy.min_delay * 2u32.pow(attempt);
+            let delay = policy.delay(attempt, None);
+            
+            assert!(delay >= backoff / 2 && delay <= backoff);
+        }
+        
+        assert!(policy.delay(20, None) <= policy.max_delay);
+        assert!(policy.delay(0, Some(Duration::from_secs(5))) >= Duration::from_secs(5));
+    }
+}
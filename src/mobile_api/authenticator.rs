@@ -0,0 +1,86 @@
+use serde::{Serialize, Deserialize};
+use std::{fs, path::Path};
+use crate::{
+    login,
+    mobile_api::TimeSync,
+    error::{FileError, LoginError},
+};
+
+/// The secrets for a mobile authenticator. Can be loaded from an SDA-style maFile.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MobileAuthenticator {
+    /// Used for generating Steam Guard codes.
+    pub shared_secret: String,
+    /// Used for confirming trades and market listings.
+    pub identity_secret: String,
+    /// The device ID the authenticator was set up with e.g. `"android:..."`. A device ID derived
+    /// from the account's SteamID is used when this is not set.
+    #[serde(default)]
+    pub device_id: Option<String>,
+    #[serde(default)]
+    pub revocation_code: Option<String>,
+    #[serde(default)]
+    pub serial_number: Option<String>,
+    #[serde(default)]
+    pub account_name: Option<String>,
+}
+
+impl MobileAuthenticator {
+    pub fn new(shared_secret: String, identity_secret: String) -> Self {
+        Self {
+            shared_secret,
+            identity_secret,
+            device_id: None,
+            revocation_code: None,
+            serial_number: None,
+            account_name: None,
+        }
+    }
+    
+    /// Parses an authenticator from the JSON contents of a maFile.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+    
+    /// Loads an authenticator from a maFile.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, FileError> {
+        let json = fs::read_to_string(path)?;
+        
+        Ok(Self::from_json(&json)?)
+    }
+    
+    /// Generates a Steam Guard code for the current server time.
+    pub fn generate_auth_code(&self, time_sync: &TimeSync) -> Result<String, LoginError> {
+        login::generate_auth_code(&self.shared_secret, time_sync.server_timestamp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_mafile() {
+        let authenticator = MobileAuthenticator::from_json(r#"{
+            "shared_secret": "MDEyMzQ1Njc4OTAxMjM0NTY3ODk=",
+            "serial_number": "1234567890123456789",
+            "revocation_code": "R12345",
+            "uri": "otpauth://totp/Steam:account?secret=ABC&issuer=Steam",
+            "server_time": 1644323205,
+            "account_name": "account",
+            "token_gid": "abc",
+            "identity_secret": "OTg3NjU0MzIxMDk4NzY1NDMyMTA=",
+            "secret_1": "c2VjcmV0",
+            "status": 1,
+            "device_id": "android:0a1b2c3d-4e5f-6a7b-8c9d-0e1f2a3b4c5d",
+            "fully_enrolled": true,
+            "Session": {
+                "SteamID": 76561198080179568
+            }
+        }"#).unwrap();
+        
+        assert_eq!(authenticator.device_id.as_deref(), Some("android:0a1b2c3d-4e5f-6a7b-8c9d-0e1f2a3b4c5d"));
+        assert_eq!(authenticator.revocation_code.as_deref(), Some("R12345"));
+        assert_eq!(authenticator.generate_auth_code(&TimeSync::new("https://api.steampowered.com")).unwrap().len(), 5);
+    }
+}
@@ -1,39 +1,123 @@
-/// A mobile confirmation. Used primarily for confirming trade offers or listing 
+use serde::Deserialize;
+use chrono::serde::ts_seconds;
+use crate::{
+    ServerTime,
+    error::Error,
+    serializers::string,
+};
+
+/// A mobile confirmation. Used primarily for confirming trade offers or listing
 /// items on the market.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct Confirmation {
+    #[serde(with = "string")]
     pub id: u64,
+    #[serde(rename = "nonce")]
+    #[serde(with = "string")]
     pub key: u64,
     /// Trade offer ID or market transaction ID.
+    #[serde(rename = "creator_id")]
+    #[serde(with = "string")]
     pub creator: u64,
+    #[serde(rename = "type")]
     pub conf_type: ConfirmationType,
-    pub description: String,
+    /// When the confirmation was created.
+    #[serde(with = "ts_seconds")]
+    pub creation_time: ServerTime,
+    /// The URL of the confirmation's icon e.g. the trade partner's avatar.
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// The headline e.g. the trade partner's name.
+    #[serde(default)]
+    pub headline: String,
+    /// Lines summarizing the confirmation e.g. the items being traded.
+    #[serde(default)]
+    pub summary: Vec<String>,
 }
 
 impl Confirmation {
     /// Human readable representation of this confirmation.
     pub fn description(&self) -> String {
-        format!("{:?} - {}", self.conf_type, self.description)
+        format!("{:?} - {} - {}", self.conf_type, self.headline, self.summary.join(", "))
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(from = "u32")]
 pub enum ConfirmationType {
     Generic = 1,
     Trade = 2,
     MarketSell = 3,
+    FeatureOptOut = 4,
+    PhoneNumberChange = 5,
     AccountRecovery = 6,
+    ApiKeyCreation = 9,
+    JoinSteamFamily = 11,
     Unknown,
 }
 
+impl From<u32> for ConfirmationType {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => ConfirmationType::Generic,
+            2 => ConfirmationType::Trade,
+            3 => ConfirmationType::MarketSell,
+            4 => ConfirmationType::FeatureOptOut,
+            5 => ConfirmationType::PhoneNumberChange,
+            6 => ConfirmationType::AccountRecovery,
+            9 => ConfirmationType::ApiKeyCreation,
+            11 => ConfirmationType::JoinSteamFamily,
+            _ => ConfirmationType::Unknown,
+        }
+    }
+}
+
 impl From<&str> for ConfirmationType {
     fn from(text: &str) -> Self {
-        match text {
-            "1" => ConfirmationType::Generic,
-            "2" => ConfirmationType::Trade,
-            "3" => ConfirmationType::MarketSell,
-            "6" => ConfirmationType::AccountRecovery,
-            _ => ConfirmationType::Unknown,
+        match text.parse::<u32>() {
+            Ok(value) => ConfirmationType::from(value),
+            Err(_) => ConfirmationType::Unknown,
         }
     }
+}
+
+/// The result of accepting or declining a confirmation as part of a batch.
+#[derive(Debug)]
+pub struct ConfirmationResult {
+    /// The ID of the confirmation.
+    pub id: u64,
+    pub result: Result<(), Error>,
+}
+
+/// The response from `/mobileconf/getlist`.
+#[derive(Deserialize, Debug)]
+pub struct GetConfirmationsResponse {
+    pub success: bool,
+    /// Whether the session or confirmation hash was rejected.
+    #[serde(default)]
+    pub needauth: bool,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub conf: Vec<Confirmation>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_get_confirmations_response() {
+        let response: GetConfirmationsResponse = serde_json::from_str(include_str!("../api/fixtures/mobileconf_getlist.json")).unwrap();
+        let confirmation = response.conf.first().unwrap();
+        
+        assert!(response.success);
+        assert_eq!(confirmation.conf_type, ConfirmationType::Trade);
+        assert_eq!(confirmation.creator, 4990667216);
+        assert_eq!(confirmation.key, 8471912311520713235);
+        assert_eq!(confirmation.creation_time.timestamp(), 1644323205);
+        assert_eq!(confirmation.summary.len(), 2);
+        assert_eq!(response.conf[1].conf_type, ConfirmationType::ApiKeyCreation);
+        assert_eq!(response.conf[1].icon, None);
+    }
 }
\ No newline at end of file
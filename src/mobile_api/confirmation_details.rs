@@ -0,0 +1,136 @@
+use std::sync::Arc;
+use serde::Deserialize;
+use scraper::{Html, Selector};
+use lazy_regex::regex_captures;
+use crate::{
+    error::ParseHtmlError,
+    serializers::{string, option_string_0_as_none},
+    response::{ClassInfo, asset::Asset, trade_offer::TradeOffer},
+    types::{AppId, ContextId, AssetId, Amount, ClassId, InstanceId, TradeOfferId},
+};
+
+/// What a confirmation will do when it is accepted.
+#[derive(Debug)]
+pub enum ConfirmationDetails {
+    /// The trade offer linked to the confirmation.
+    Trade(TradeOffer),
+    /// The item being listed on the market.
+    MarketListing {
+        item: Asset,
+        /// The prices as displayed on the details page e.g. `"You receive: $0.03"`.
+        prices: Vec<String>,
+    },
+    /// Any other confirmation. Contains the HTML of the details page.
+    Other(String),
+}
+
+/// The item in a market listing confirmation.
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct RawConfirmationItem {
+    pub appid: AppId,
+    #[serde(with = "string")]
+    pub contextid: ContextId,
+    #[serde(rename = "id")]
+    #[serde(with = "string")]
+    pub assetid: AssetId,
+    #[serde(with = "string")]
+    pub classid: ClassId,
+    #[serde(default)]
+    #[serde(with = "option_string_0_as_none")]
+    pub instanceid: InstanceId,
+    #[serde(with = "string")]
+    pub amount: Amount,
+}
+
+/// The details of a confirmation parsed from `/mobileconf/details/{id}`.
+#[derive(Debug, PartialEq)]
+pub enum RawConfirmationDetails {
+    Trade {
+        tradeofferid: TradeOfferId,
+    },
+    MarketListing {
+        item: RawConfirmationItem,
+        /// The item's description from the hover data, if it is complete.
+        classinfo: Option<Arc<ClassInfo>>,
+        prices: Vec<String>,
+    },
+    Other(String),
+}
+
+/// Builds the classinfo of a market listing item from its hover data. Fields the hover data 
+/// leaves out are filled with defaults.
+fn parse_hover_classinfo(mut value: serde_json::Value) -> Option<Arc<ClassInfo>> {
+    let object = value.as_object_mut()?;
+    
+    object.entry("icon_url_large").or_insert_with(|| "".into());
+    object.entry("market_tradable_restriction").or_insert_with(|| 0.into());
+    object.entry("market_marketable_restriction").or_insert_with(|| 0.into());
+    
+    serde_json::from_value(value).ok().map(Arc::new)
+}
+
+/// Parses the HTML from the details of a confirmation.
+pub fn parse_confirmation_details(html: &str) -> Result<RawConfirmationDetails, ParseHtmlError> {
+    if let Some((_, tradeofferid)) = regex_captures!(r#"id="tradeofferid_(\d+)""#, html) {
+        return Ok(RawConfirmationDetails::Trade {
+            tradeofferid: tradeofferid.parse::<TradeOfferId>()?,
+        });
+    }
+    
+    if let Some((_, json)) = regex_captures!(r#"BuildHover\(\s*'confiteminfo',\s*(\{.*\}),\s*UserYou\s*\);"#, html) {
+        let value = serde_json::from_str::<serde_json::Value>(json)
+            .map_err(|_| ParseHtmlError::Malformed("Invalid confirmation item"))?;
+        let item = RawConfirmationItem::deserialize(&value)
+            .map_err(|_| ParseHtmlError::Malformed("Invalid confirmation item"))?;
+        let classinfo = parse_hover_classinfo(value);
+        let fragment = Html::parse_fragment(html);
+        let selector = Selector::parse(".mobileconf_listing_price")
+            .map_err(|_| ParseHtmlError::Malformed("Invalid selector"))?;
+        let prices = fragment.select(&selector)
+            .map(|element| element.text().flat_map(|text| text.split_whitespace()).collect::<Vec<_>>().join(" "))
+            .collect();
+        
+        return Ok(RawConfirmationDetails::MarketListing {
+            item,
+            classinfo,
+            prices,
+        });
+    }
+    
+    Ok(RawConfirmationDetails::Other(html.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_trade_confirmation_details() {
+        let details = parse_confirmation_details(include_str!("../api/fixtures/mobileconf_details_trade.html")).unwrap();
+        
+        assert_eq!(details, RawConfirmationDetails::Trade {
+            tradeofferid: 4990667216,
+        });
+    }
+    
+    #[test]
+    fn parses_market_confirmation_details() {
+        let details = parse_confirmation_details(include_str!("../api/fixtures/mobileconf_details_market.html")).unwrap();
+        
+        if let RawConfirmationDetails::MarketListing { item, classinfo, prices } = details {
+            assert_eq!(item.assetid, 11152148507);
+            assert_eq!(item.classid, 101785959);
+            assert_eq!(item.instanceid, Some(11040578));
+            
+            let classinfo = classinfo.unwrap();
+            
+            assert_eq!(classinfo.classid, 101785959);
+            assert_eq!(classinfo.market_hash_name, "Mann Co. Supply Crate Key");
+            assert_eq!(classinfo.r#type, "Level 5 Tool");
+            assert!(classinfo.marketable);
+            assert_eq!(prices, vec!["You receive: $0.03", "Buyer pays: $0.05"]);
+        } else {
+            panic!("Expected market listing details");
+        }
+    }
+}
@@ -1,20 +1,16 @@
-use super::confirmation::{Confirmation, ConfirmationType};
-use crate::{
-    SteamID,
-    time,
-    error::ParseHtmlError,
-};
+use crate::SteamID;
+use super::Confirmation;
+use std::collections::HashMap;
 use hmacsha1::hmac_sha1;
 use sha1::{Sha1, Digest};
 use lazy_regex::regex_replace_all;
-use scraper::{Html, Selector, element_ref::ElementRef};
 
 pub fn build_time_bytes(time: i64) -> [u8; 8] {
     time.to_be_bytes()
 }
     
 pub fn generate_confirmation_hash_for_time(time: i64, tag: &str, identity_secret: &String) -> String {
-    let decode: &[u8] = &base64::decode(&identity_secret).unwrap();
+    let decode: &[u8] = &base64::decode(identity_secret).unwrap();
     let time_bytes = build_time_bytes(time);
     let tag_bytes = tag.as_bytes();
     let array = [&time_bytes, tag_bytes].concat();
@@ -23,6 +19,30 @@ pub fn generate_confirmation_hash_for_time(time: i64, tag: &str, identity_secret
     base64::encode(hash)
 }
 
+/// Generates a Steam Guard code from a base64-encoded shared secret for the given time.
+pub fn generate_auth_code(shared_secret: &str, time: i64) -> Result<String, base64::DecodeError> {
+    const CHARS: &[u8] = b"23456789BCDFGHJKMNPQRTVWXY";
+    
+    let decode = base64::decode(shared_secret)?;
+    let time_bytes = build_time_bytes(time / 30);
+    let hash = hmac_sha1(&decode, &time_bytes);
+    let start = (hash[19] & 0x0f) as usize;
+    let mut full_code = u32::from_be_bytes([
+        hash[start],
+        hash[start + 1],
+        hash[start + 2],
+        hash[start + 3],
+    ]) & 0x7fffffff;
+    let mut code = String::with_capacity(5);
+    
+    for _ in 0..5 {
+        code.push(CHARS[full_code as usize % CHARS.len()] as char);
+        full_code /= CHARS.len() as u32;
+    }
+    
+    Ok(code)
+}
+
 pub fn get_device_id(steamid: &SteamID) -> String {
     let mut hasher = Sha1::new();
 
@@ -41,71 +61,47 @@ pub fn get_device_id(steamid: &SteamID) -> String {
     format!("android:{}", device_id)
 }
 
-pub fn parse_confirmations(text: String) -> Result<Vec<Confirmation>, ParseHtmlError> {
-    fn parse_description(element: ElementRef, description_selector: &Selector) -> Result<Confirmation, ParseHtmlError> {
-        let description: Option<_> = element.select(description_selector).next();
-        let data_type = element.value().attr("data-type");
-        let id = element.value().attr("data-confid");
-        let key = element.value().attr("data-key");
-        let creator = element.value().attr("data-creator");
-        
-        // check contents before unwrapping
-        if description.is_none() || data_type.is_none() || key.is_none() || creator.is_none() {
-            return Err(ParseHtmlError::Malformed("Unexpected description format"));
-        }
-        
-        let description = description
-            .unwrap()
-            .text()
-            .map(|t| t.trim())
-            .filter(|t| !t.is_empty())
-            .collect::<Vec<_>>()
-            .join(" ");
-        let conf_type = data_type
-            .unwrap()
-            .try_into()
-            .unwrap_or(ConfirmationType::Unknown);
-        
-        Ok(Confirmation {
-            id: id.unwrap().parse::<u64>()?,
-            key: key.unwrap().parse::<u64>()?,
-            conf_type,
-            description,
-            creator: creator.unwrap().parse::<u64>()?,
-        })
-    }
-
-    let fragment = Html::parse_fragment(&text);
-    // these should probably never fail
-    let mobileconf_empty_selector = Selector::parse("#mobileconf_empty").unwrap();
-    let mobileconf_done_selector = Selector::parse(".mobileconf_done").unwrap();
-    let div_selector = Selector::parse("div").unwrap();
+/// Builds the form for `/mobileconf/multiajaxop`. Each confirmation is added as a `cid[]` and
+/// `ck[]` pair.
+pub fn build_multi_confirmation_form<'a>(
+    query: HashMap<&'a str, String>,
+    operation: &str,
+    confirmations: &[Confirmation],
+) -> Vec<(&'a str, String)> {
+    let mut form = query.into_iter().collect::<Vec<_>>();
     
-    if let Some(element) = fragment.select(&mobileconf_empty_selector).next() {
-        if mobileconf_done_selector.matches(&element) {
-            if let Some(element) = element.select(&div_selector).nth(1) {
-                let error_message = element
-                    .text()
-                    .collect::<String>();
-                
-                return Err(ParseHtmlError::Response(error_message));
-            } else {
-                return Ok(Vec::new());
-            }
-        } else {
-            return Ok(Vec::new());
-        }
-    }
+    form.push(("op", operation.into()));
     
-    let confirmation_list_selector = Selector::parse(".mobileconf_list_entry").unwrap();
-    let description_selector = Selector::parse(".mobileconf_list_entry_description").unwrap();
-    let confirmations = fragment.select(&confirmation_list_selector)
-        .map(|description| parse_description(description, &description_selector))
-        .collect::<Result<Vec<Confirmation>, ParseHtmlError>>()?;
+    for confirmation in confirmations {
+        form.push(("cid[]", confirmation.id.to_string()));
+        form.push(("ck[]", confirmation.key.to_string()));
+    }
     
-    Ok(confirmations)
+    form
 }
 
-pub fn server_time(time_offset: i64) -> i64 {
-    time::get_system_time() as i64 + time_offset
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn generates_auth_code() {
+        let code = generate_auth_code("MDEyMzQ1Njc4OTAxMjM0NTY3ODk=", 1634603498).unwrap();
+        
+        assert_eq!(code, "MK8Q3");
+    }
+    
+    #[test]
+    fn builds_multi_confirmation_form() {
+        let response: crate::mobile_api::confirmation::GetConfirmationsResponse = serde_json::from_str(include_str!("../api/fixtures/mobileconf_getlist.json")).unwrap();
+        let form = build_multi_confirmation_form(HashMap::new(), "allow", &response.conf);
+        
+        assert_eq!(form, vec![
+            ("op", "allow".to_string()),
+            ("cid[]", "13141210843".to_string()),
+            ("ck[]", "8471912311520713235".to_string()),
+            ("cid[]", "13141210844".to_string()),
+            ("ck[]", "3427151020981238452".to_string()),
+        ]);
+    }
 }
\ No newline at end of file
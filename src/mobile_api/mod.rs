@@ -1,15 +1,26 @@
+mod authenticator;
 mod confirmation;
+mod confirmation_details;
 mod helpers;
+mod time_sync;
 
-pub use confirmation::{Confirmation, ConfirmationType};
+pub use authenticator::MobileAuthenticator;
+pub use confirmation::{Confirmation, ConfirmationType, ConfirmationResult};
+pub use confirmation_details::{ConfirmationDetails, RawConfirmationDetails};
+
+use confirmation::GetConfirmationsResponse;
+pub use helpers::generate_auth_code;
+
+pub use time_sync::TimeSync;
 
 use serde::Deserialize;
 use reqwest::cookie::Jar;
 use url::{Url, ParseError};
 use reqwest_middleware::ClientWithMiddleware;
-use std::{collections::HashMap, sync::{Arc, RwLock}};
+use std::{collections::{HashMap, HashSet}, sync::{Arc, RwLock}};
 use crate::{
     SteamID,
+    MiddlewareOptions,
     error::Error,
     helpers::{
         get_default_middleware,
@@ -18,16 +29,20 @@ use crate::{
 };
 
 const HOSTNAME: &str = "https://steamcommunity.com";
+const API_HOSTNAME: &str = "https://api.steampowered.com";
 const USER_AGENT_STRING: &str = "Mozilla/5.0 (Linux; U; Android 4.1.1; en-us; Google Nexus 4 - 4.1.1 - API 16 - 768x1280 Build/JRO03S) AppleWebKit/534.30 (KHTML, like Gecko) Version/4.0 Mobile Safari/534.30";
 
 #[derive(Debug)]
 pub struct MobileAPI {
     client: ClientWithMiddleware,
     pub cookies: Arc<Jar>,
-    pub language: String,
     pub steamid: SteamID,
     pub identity_secret: Option<String>,
+    /// The device ID used for confirmations. Derived from the SteamID when not set.
+    pub device_id: Option<String>,
     pub sessionid: Arc<RwLock<Option<String>>>,
+    /// Keeps the time used for confirmation hashes in sync with Steam.
+    pub time_sync: Arc<TimeSync>,
 }
 
 impl MobileAPI {
@@ -36,13 +51,15 @@ impl MobileAPI {
         steamid: SteamID,
         language: String,
         identity_secret: Option<String>,
+        device_id: Option<String>,
+        middleware_options: &MiddlewareOptions,
     ) -> Self {
         let url = HOSTNAME.parse::<Url>().unwrap();
-        let client = get_default_middleware(Arc::clone(&cookies), USER_AGENT_STRING);
+        let client = get_default_middleware(Arc::clone(&cookies), USER_AGENT_STRING, middleware_options);
         
         cookies.add_cookie_str("mobileClientVersion=0 (2.1.3)", &url);
         cookies.add_cookie_str("mobileClient=android", &url);
-        cookies.add_cookie_str("Steam_Language=english", &url);
+        cookies.add_cookie_str(format!("Steam_Language={}", language).as_str(), &url);
         cookies.add_cookie_str("dob=", &url);
         cookies.add_cookie_str(format!("steamid={}", u64::from(steamid)).as_str(), &url);
         
@@ -50,9 +67,10 @@ impl MobileAPI {
             client,
             steamid,
             identity_secret,
-            language,
+            device_id,
             cookies,
             sessionid: Arc::new(RwLock::new(None)),
+            time_sync: Arc::new(TimeSync::new(API_HOSTNAME)),
         }
     }
     
@@ -81,13 +99,19 @@ impl MobileAPI {
         Ok(())
     }
     
+    /// Syncs the time with Steam. The time is otherwise synced when needed for confirmations.
+    pub async fn sync_time(&self) -> Result<(), Error> {
+        self.time_sync.sync(&self.client).await
+    }
+    
     async fn get_confirmation_query_params<'a>(&self, tag: &str) -> Result<HashMap<&'a str, String>, Error> {
         if self.identity_secret.is_none() {
             return Err(Error::Parameter("No identity secret"));
         }
         
-        // let time = self.get_server_time().await?;
-        let time = helpers::server_time(0);
+        self.time_sync.sync_if_needed(&self.client).await;
+        
+        let time = self.time_sync.server_timestamp();
         let key = helpers::generate_confirmation_hash_for_time(
             time,
             tag,
@@ -96,8 +120,12 @@ impl MobileAPI {
         );
         let mut params: HashMap<&str, String> = HashMap::new();
         
-        // self.device_id.clone()
-        params.insert("p", helpers::get_device_id(&self.steamid));
+        let device_id = match &self.device_id {
+            Some(device_id) => device_id.clone(),
+            None => helpers::get_device_id(&self.steamid),
+        };
+        
+        params.insert("p", device_id);
         params.insert("a", u64::from(self.steamid).to_string());
         params.insert("k", key);
         params.insert("t", time.to_string());
@@ -107,13 +135,19 @@ impl MobileAPI {
         Ok(params)
     }
     
+    /// Called when Steam rejects a confirmation request.
+    fn confirmation_rejected(&self) {
+        // the hash may have been rejected because our time is out of sync
+        self.time_sync.invalidate();
+    }
+    
     pub async fn send_confirmation_ajax(&self, confirmation: &Confirmation, operation: String) -> Result<(), Error>  {
         #[derive(Debug, Clone, Copy, Deserialize)]
         struct SendConfirmationResponse {
             pub success: bool,
         }
         
-        let mut query = self.get_confirmation_query_params("conf").await?;
+        let mut query = self.get_confirmation_query_params(&operation).await?;
         
         query.insert("op", operation);
         query.insert("cid", confirmation.id.to_string());
@@ -129,6 +163,8 @@ impl MobileAPI {
         let body: SendConfirmationResponse = parses_response(response).await?;
         
         if !body.success {
+            self.confirmation_rejected();
+            
             return Err(Error::Response("Confirmation unsuccessful. The confirmation may no longer exist or another trade may be going through. Check confirmations again to verify.".into()));
         }
         
@@ -139,21 +175,143 @@ impl MobileAPI {
         self.send_confirmation_ajax(confirmation, "allow".into()).await
     }
 
-    pub async fn deny_confirmation(&self, confirmation: &Confirmation) -> Result<(), Error> {
+    pub async fn decline_confirmation(&self, confirmation: &Confirmation) -> Result<(), Error> {
         self.send_confirmation_ajax(confirmation, "cancel".into()).await
     }
     
+    /// Accepts or declines confirmations in one request. If the batch fails, confirmations that 
+    /// are still pending are retried one at a time so that each gets its own result. Those no 
+    /// longer pending get [`Error::ConfirmationNoLongerPending`], and all get 
+    /// [`Error::ConfirmationStatusUnknown`] if the pending confirmations can't be checked.
+    pub async fn send_multi_confirmation_ajax(
+        &self,
+        confirmations: &[Confirmation],
+        operation: &str,
+    ) -> Result<Vec<ConfirmationResult>, Error> {
+        #[derive(Debug, Clone, Copy, Deserialize)]
+        struct SendConfirmationResponse {
+            pub success: bool,
+        }
+        
+        if confirmations.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let query = self.get_confirmation_query_params(operation).await?;
+        let form = helpers::build_multi_confirmation_form(query, operation, confirmations);
+        let uri = self.get_uri("/mobileconf/multiajaxop");
+        let response = self.client.post(&uri)
+            .header("X-Requested-With", "com.valvesoftware.android.steam.community")
+            .form(&form)
+            .send()
+            .await?;
+        let body: SendConfirmationResponse = parses_response(response).await?;
+        
+        if body.success {
+            return Ok(confirmations
+                .iter()
+                .map(|confirmation| ConfirmationResult {
+                    id: confirmation.id,
+                    result: Ok(()),
+                })
+                .collect());
+        }
+        
+        self.confirmation_rejected();
+        
+        // some of the batch may have gone through, but there's no telling for those no longer pending
+        let pending = match self.get_trade_confirmations().await {
+            Ok(pending) => pending
+                .into_iter()
+                .map(|confirmation| confirmation.id)
+                .collect::<HashSet<_>>(),
+            Err(error) => {
+                let message = error.to_string();
+                
+                return Ok(confirmations
+                    .iter()
+                    .map(|confirmation| ConfirmationResult {
+                        id: confirmation.id,
+                        result: Err(Error::ConfirmationStatusUnknown(confirmation.id, message.clone())),
+                    })
+                    .collect());
+            },
+        };
+        let mut results = Vec::with_capacity(confirmations.len());
+        
+        for confirmation in confirmations {
+            let result = if pending.contains(&confirmation.id) {
+                self.send_confirmation_ajax(confirmation, operation.into()).await
+            } else {
+                Err(Error::ConfirmationNoLongerPending(confirmation.id))
+            };
+            
+            results.push(ConfirmationResult {
+                id: confirmation.id,
+                result,
+            });
+        }
+        
+        Ok(results)
+    }
+    
+    /// Accepts confirmations in one request.
+    pub async fn accept_confirmations(&self, confirmations: &[Confirmation]) -> Result<Vec<ConfirmationResult>, Error> {
+        self.send_multi_confirmation_ajax(confirmations, "allow").await
+    }
+    
+    /// Declines confirmations in one request.
+    pub async fn decline_confirmations(&self, confirmations: &[Confirmation]) -> Result<Vec<ConfirmationResult>, Error> {
+        self.send_multi_confirmation_ajax(confirmations, "cancel").await
+    }
+    
+    /// Gets the details of a confirmation from its details page.
+    pub async fn get_confirmation_details(&self, confirmation: &Confirmation) -> Result<RawConfirmationDetails, Error> {
+        #[derive(Debug, Deserialize)]
+        struct GetConfirmationDetailsResponse {
+            pub success: bool,
+            #[serde(default)]
+            pub html: String,
+        }
+        
+        let query = self.get_confirmation_query_params("details").await?;
+        let uri = self.get_uri(&format!("/mobileconf/details/{}", confirmation.id));
+        let response = self.client.get(&uri)
+            .header("X-Requested-With", "com.valvesoftware.android.steam.community")
+            .query(&query)
+            .send()
+            .await?;
+        let body: GetConfirmationDetailsResponse = parses_response(response).await?;
+        
+        if !body.success {
+            self.confirmation_rejected();
+            
+            return Err(Error::Response("Failed to get confirmation details. The confirmation may no longer exist.".into()));
+        }
+        
+        Ok(confirmation_details::parse_confirmation_details(&body.html)?)
+    }
+    
     pub async fn get_trade_confirmations(&self) -> Result<Vec<Confirmation>, Error> {
-        let uri = self.get_uri("/mobileconf/conf");
-        let query = self.get_confirmation_query_params("conf").await?;
+        let uri = self.get_uri("/mobileconf/getlist");
+        let query = self.get_confirmation_query_params("list").await?;
         let response = self.client.get(&uri)
             .header("X-Requested-With", "com.valvesoftware.android.steam.community")
             .query(&query)
             .send()
             .await?;
-        let body = response.text().await?;
-        let confirmations = helpers::parse_confirmations(body)?;
+        let body: GetConfirmationsResponse = parses_response(response).await?;
+        
+        if body.needauth {
+            return Err(Error::NotLoggedIn);
+        }
+        
+        if !body.success {
+            self.confirmation_rejected();
+            
+            return Err(Error::Response(body.message.unwrap_or_else(|| "Unable to get confirmations".into())));
+        }
         
-        Ok(confirmations)
+        Ok(body.conf)
     }
 }
@@ -0,0 +1,156 @@
+use std::{
+    sync::{Mutex, atomic::{AtomicI64, Ordering}},
+    time::{Duration, Instant},
+};
+use serde::Deserialize;
+use reqwest_middleware::ClientWithMiddleware;
+use log::warn;
+use crate::{
+    ServerTime,
+    time,
+    error::Error,
+    serializers::string,
+    helpers::parses_response,
+};
+
+/// How often the time is synced if Steam does not say otherwise.
+const DEFAULT_PROBE_FREQUENCY_SECS: u64 = 3600;
+
+#[derive(Deserialize, Debug)]
+struct QueryTimeResponse {
+    #[serde(with = "string")]
+    server_time: i64,
+    #[serde(default)]
+    probe_frequency_seconds: Option<u64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct QueryTimeBody {
+    response: QueryTimeResponse,
+}
+
+#[derive(Debug)]
+struct SyncState {
+    /// When the time was last synced.
+    synced_at: Instant,
+    /// How long until the time should be synced again.
+    probe_frequency: Duration,
+}
+
+/// Keeps the offset between Steam's clock and the system clock in sync using
+/// ITwoFactorService/QueryTime.
+#[derive(Debug)]
+pub struct TimeSync {
+    api_hostname: String,
+    /// The difference in seconds between Steam's clock and the system clock.
+    offset: AtomicI64,
+    state: Mutex<Option<SyncState>>,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl TimeSync {
+    /// Creates a time sync which queries the time from the given Web API hostname e.g. 
+    /// `"https://api.steampowered.com"`.
+    pub fn new(api_hostname: &str) -> Self {
+        Self {
+            api_hostname: api_hostname.to_string(),
+            offset: AtomicI64::new(0),
+            state: Mutex::new(None),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+    
+    /// Gets the difference in seconds between Steam's clock and the system clock.
+    pub fn offset(&self) -> i64 {
+        self.offset.load(Ordering::Relaxed)
+    }
+    
+    /// Gets the current timestamp on Steam's servers.
+    pub fn server_timestamp(&self) -> i64 {
+        time::get_system_time() as i64 + self.offset()
+    }
+    
+    /// Gets the current time on Steam's servers.
+    pub fn server_time_now(&self) -> ServerTime {
+        time::get_system_time_now() + chrono::Duration::seconds(self.offset())
+    }
+    
+    /// Checks whether the time has never been synced or the last sync is outdated.
+    fn needs_sync(&self) -> bool {
+        match &*self.state.lock().unwrap() {
+            Some(state) => state.synced_at.elapsed() >= state.probe_frequency,
+            None => true,
+        }
+    }
+    
+    /// Syncs the time if it has never been synced or the last sync is outdated. Failures are
+    /// logged and the current offset is kept.
+    pub async fn sync_if_needed(&self, client: &ClientWithMiddleware) {
+        if !self.needs_sync() {
+            return;
+        }
+        
+        let _lock = self.lock.lock().await;
+        
+        // another task may have synced while we were waiting
+        if !self.needs_sync() {
+            return;
+        }
+        
+        if let Err(error) = self.sync(client).await {
+            warn!("Error syncing time with Steam: {}", error);
+        }
+    }
+    
+    /// Syncs the time with Steam.
+    pub async fn sync(&self, client: &ClientWithMiddleware) -> Result<(), Error> {
+        let uri = format!("{}/ITwoFactorService/QueryTime/v1/", self.api_hostname);
+        let response = client.post(uri)
+            .form(&[("steamid", "0")])
+            .send()
+            .await?;
+        let body: QueryTimeBody = parses_response(response).await?;
+        
+        self.apply(body.response, time::get_system_time() as i64);
+        
+        Ok(())
+    }
+    
+    /// Marks the time as needing to be synced again e.g. after a confirmation hash was rejected.
+    pub fn invalidate(&self) {
+        *self.state.lock().unwrap() = None;
+    }
+    
+    fn apply(&self, response: QueryTimeResponse, system_time: i64) {
+        let probe_frequency_secs = response.probe_frequency_seconds
+            .unwrap_or(DEFAULT_PROBE_FREQUENCY_SECS);
+        
+        self.offset.store(response.server_time - system_time, Ordering::Relaxed);
+        *self.state.lock().unwrap() = Some(SyncState {
+            synced_at: Instant::now(),
+            probe_frequency: Duration::from_secs(probe_frequency_secs),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn applies_query_time_response() {
+        let body: QueryTimeBody = serde_json::from_str(include_str!("../api/fixtures/query_time.json")).unwrap();
+        let time_sync = TimeSync::new("https://api.steampowered.com");
+        
+        assert!(time_sync.needs_sync());
+        
+        time_sync.apply(body.response, 1644323145);
+        
+        assert_eq!(time_sync.offset(), 60);
+        assert!(!time_sync.needs_sync());
+        
+        time_sync.invalidate();
+        
+        assert!(time_sync.needs_sync());
+    }
+}
@@ -1,2 +1,3 @@
 pub mod trade_offer;
+pub mod trade_history;
 pub mod serializers;
\ No newline at end of file
@@ -0,0 +1,52 @@
+use crate::{
+    time::ServerTime,
+    types::TradeId,
+    response::trade::Trade,
+};
+
+/// Options for getting trade history. Trades are returned newest first. To get the next page, 
+/// set `start_after_time` and `start_after_tradeid` to those of the last trade in the previous
+/// page, or use [`TradeHistoryOptions::next_page`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeHistoryOptions {
+    /// The maximum number of trades to get. Steam allows at most 500.
+    pub max_trades: u32,
+    /// Only get trades initiated before this time, or after it when navigating back.
+    pub start_after_time: Option<ServerTime>,
+    /// The ID of the last trade from the previous page.
+    pub start_after_tradeid: Option<TradeId>,
+    /// Whether to page towards newer trades rather than older ones.
+    pub navigating_back: bool,
+    /// Whether to include failed trades.
+    pub include_failed: bool,
+}
+
+impl Default for TradeHistoryOptions {
+    fn default() -> Self {
+        Self {
+            max_trades: 100,
+            start_after_time: None,
+            start_after_tradeid: None,
+            navigating_back: false,
+            include_failed: false,
+        }
+    }
+}
+
+impl TradeHistoryOptions {
+    /// Gets the options for the page of trades following the given trades. Returns `None` if 
+    /// `trades` is empty.
+    pub fn next_page(&self, trades: &[Trade]) -> Option<Self> {
+        let last = if self.navigating_back {
+            trades.first()
+        } else {
+            trades.last()
+        }?;
+        
+        Some(Self {
+            start_after_time: Some(last.time_init),
+            start_after_tradeid: Some(last.tradeid),
+            ..self.clone()
+        })
+    }
+}
@@ -1,10 +1,12 @@
 use super::{Item, NewTradeOffer};
-use crate::SteamID;
+use crate::{SteamID, TradeUrl, response::Currency};
 
 pub struct NewTradeOfferBuilder {
     pub partner: SteamID,
     pub items_to_give: Vec<Item>,
     pub items_to_receive: Vec<Item>,
+    pub currency_to_give: Vec<Currency>,
+    pub currency_to_receive: Vec<Currency>,
     pub message: Option<String>,
     pub token: Option<String>,
 }
@@ -16,11 +18,21 @@ impl NewTradeOfferBuilder {
             partner,
             items_to_give: Vec::new(),
             items_to_receive: Vec::new(),
+            currency_to_give: Vec::new(),
+            currency_to_receive: Vec::new(),
             message: None,
             token: None,
         }
     }
 
+    /// Creates a builder for an offer to the partner in a trade URL, using its token.
+    pub fn from_trade_url(trade_url: TradeUrl) -> Self {
+        Self {
+            token: trade_url.token,
+            ..Self::new(trade_url.partner)
+        }
+    }
+
     pub fn items_to_give(mut self, items: Vec<Item>) -> Self {
         self.items_to_give = items;
         self
@@ -31,6 +43,16 @@ impl NewTradeOfferBuilder {
         self
     }
 
+    pub fn currency_to_give(mut self, currency: Vec<Currency>) -> Self {
+        self.currency_to_give = currency;
+        self
+    }
+
+    pub fn currency_to_receive(mut self, currency: Vec<Currency>) -> Self {
+        self.currency_to_receive = currency;
+        self
+    }
+
     pub fn token(mut self, token: String) -> Self {
         self.token = Some(token);
         self
@@ -46,6 +68,8 @@ impl NewTradeOfferBuilder {
             partner: self.partner,
             items_to_give: self.items_to_give,
             items_to_receive: self.items_to_receive,
+            currency_to_give: self.currency_to_give,
+            currency_to_receive: self.currency_to_receive,
             message: self.message,
             token: self.token,
         }
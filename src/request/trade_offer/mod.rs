@@ -1,8 +1,10 @@
 mod item;
 mod builder;
+mod validation;
 
 pub use item::Item;
 pub use builder::NewTradeOfferBuilder;
+pub use validation::{OfferProblem, OfferSide, MAX_MESSAGE_LENGTH, MAX_ITEMS_PER_SIDE};
 use steamid_ng::SteamID;
 use crate::response;
 
@@ -13,6 +15,8 @@ pub struct NewTradeOffer {
     pub partner: SteamID,
     pub items_to_give: Vec<Item>,
     pub items_to_receive: Vec<Item>,
+    pub currency_to_give: Vec<response::Currency>,
+    pub currency_to_receive: Vec<response::Currency>,
     pub message: Option<String>,
     pub token: Option<String>,
 }
@@ -25,7 +29,9 @@ impl NewTradeOffer {
     /// Checks if any items are included in the offer.
     pub fn is_empty(&self) -> bool {
         self.items_to_give.is_empty() &&
-        self.items_to_receive.is_empty()
+        self.items_to_receive.is_empty() &&
+        self.currency_to_give.is_empty() &&
+        self.currency_to_receive.is_empty()
     }
 }
 
@@ -35,6 +41,8 @@ impl From<&response::trade_offer::TradeOffer> for NewTradeOffer {
             partner: offer.partner,
             items_to_give: from_trade_offer_items(&offer.items_to_give),
             items_to_receive: from_trade_offer_items(&offer.items_to_receive),
+            currency_to_give: offer.currency_to_give.clone(),
+            currency_to_receive: offer.currency_to_receive.clone(),
             message: None,
             token: None,
         }
@@ -47,6 +55,8 @@ impl From<response::trade_offer::TradeOffer> for NewTradeOffer {
             partner: offer.partner,
             items_to_give: from_trade_offer_items(&offer.items_to_give),
             items_to_receive: from_trade_offer_items(&offer.items_to_receive),
+            currency_to_give: offer.currency_to_give,
+            currency_to_receive: offer.currency_to_receive,
             message: None,
             token: None,
         }
@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet};
+use super::{Item, NewTradeOffer};
+use crate::{
+    response::asset::Asset,
+    types::{AppId, ContextId, AssetId, Amount},
+};
+
+/// The maximum length of a trade offer message.
+pub const MAX_MESSAGE_LENGTH: usize = 128;
+/// The maximum number of items on either side of a trade offer.
+pub const MAX_ITEMS_PER_SIDE: usize = 256;
+
+/// A side of a trade offer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OfferSide {
+    /// Items we give.
+    Give,
+    /// Items we receive.
+    Receive,
+}
+
+/// A problem found when validating a [`NewTradeOffer`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum OfferProblem {
+    /// The same asset is included more than once on a side.
+    DuplicateAsset {
+        side: OfferSide,
+        item: Item,
+    },
+    /// The asset is not in the owner's inventory.
+    MissingAsset {
+        side: OfferSide,
+        item: Item,
+    },
+    /// The asset is in our inventory but cannot be traded.
+    UntradableAsset {
+        item: Item,
+    },
+    /// The amount is zero or exceeds the number of items in the stack.
+    InvalidAmount {
+        side: OfferSide,
+        item: Item,
+        /// The number of items in the stack.
+        stack_size: Amount,
+    },
+    /// A side has more items than Steam allows.
+    TooManyItems {
+        side: OfferSide,
+        count: usize,
+    },
+    /// The message is longer than Steam allows.
+    MessageTooLong {
+        length: usize,
+    },
+}
+
+impl NewTradeOffer {
+    /// Gets the inventory contexts items are included from for a side of the offer.
+    pub fn inventory_contexts(&self, side: OfferSide) -> HashSet<(AppId, ContextId)> {
+        self.items(side)
+            .iter()
+            .map(|item| (item.appid, item.contextid))
+            .collect()
+    }
+    
+    /// Validates the offer against our inventory and the partner's inventory. Both inventories
+    /// should include untradable items and cover every context returned from
+    /// [`NewTradeOffer::inventory_contexts`] for their side. Returns an empty list if no
+    /// problems were found.
+    pub fn validate(
+        &self,
+        our_inventory: &[Asset],
+        their_inventory: &[Asset],
+    ) -> Vec<OfferProblem> {
+        let mut problems = Vec::new();
+        
+        if let Some(message) = &self.message {
+            let length = message.chars().count();
+            
+            if length > MAX_MESSAGE_LENGTH {
+                problems.push(OfferProblem::MessageTooLong { length });
+            }
+        }
+        
+        for (side, inventory) in [
+            (OfferSide::Give, our_inventory),
+            (OfferSide::Receive, their_inventory),
+        ] {
+            let items = self.items(side);
+            let count = items.len();
+            
+            if count > MAX_ITEMS_PER_SIDE {
+                problems.push(OfferProblem::TooManyItems { side, count });
+            }
+            
+            let inventory = inventory
+                .iter()
+                .map(|asset| ((asset.appid, asset.contextid, asset.assetid), asset))
+                .collect::<HashMap<(AppId, ContextId, AssetId), &Asset>>();
+            let mut seen = HashSet::new();
+            
+            for item in items {
+                let key = (item.appid, item.contextid, item.assetid);
+                
+                if !seen.insert(key) {
+                    problems.push(OfferProblem::DuplicateAsset {
+                        side,
+                        item: item.clone(),
+                    });
+                    continue;
+                }
+                
+                let asset = if let Some(asset) = inventory.get(&key) {
+                    asset
+                } else {
+                    problems.push(OfferProblem::MissingAsset {
+                        side,
+                        item: item.clone(),
+                    });
+                    continue;
+                };
+                
+                if side == OfferSide::Give && !asset.classinfo.tradable {
+                    problems.push(OfferProblem::UntradableAsset {
+                        item: item.clone(),
+                    });
+                }
+                
+                if item.amount == 0 || item.amount > asset.amount {
+                    problems.push(OfferProblem::InvalidAmount {
+                        side,
+                        item: item.clone(),
+                        stack_size: asset.amount,
+                    });
+                }
+            }
+        }
+        
+        problems
+    }
+    
+    fn items(&self, side: OfferSide) -> &[Item] {
+        match side {
+            OfferSide::Give => &self.items_to_give,
+            OfferSide::Receive => &self.items_to_receive,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::{SteamID, response::classinfo::ClassInfo};
+    
+    fn asset(assetid: AssetId, amount: Amount, tradable: bool) -> Asset {
+        let mut classinfo: ClassInfo = serde_json::from_str(r#"{
+            "classid": "101785959",
+            "instanceid": "11040578",
+            "market_name": "Mann Co. Supply Crate Key",
+            "market_hash_name": "Mann Co. Supply Crate Key",
+            "icon_url": "",
+            "icon_url_large": "",
+            "type": "Level 5 Tool",
+            "tradable": 1,
+            "marketable": 1,
+            "commodity": 1,
+            "market_tradable_restriction": "7",
+            "market_marketable_restriction": "0"
+        }"#).unwrap();
+        
+        classinfo.tradable = tradable;
+        
+        Asset {
+            appid: 440,
+            contextid: 2,
+            assetid,
+            amount,
+            classinfo: Arc::new(classinfo),
+        }
+    }
+    
+    fn item(assetid: AssetId, amount: Amount) -> Item {
+        Item {
+            appid: 440,
+            contextid: 2,
+            assetid,
+            amount,
+        }
+    }
+    
+    #[test]
+    fn validates_offer() {
+        let offer = NewTradeOffer::builder(SteamID::from(76561198080179568))
+            .items_to_give(vec![item(1, 1), item(1, 1), item(2, 1), item(3, 5)])
+            .items_to_receive(vec![item(4, 1), item(5, 1)])
+            .message("a".repeat(MAX_MESSAGE_LENGTH + 1))
+            .build();
+        let our_inventory = vec![asset(1, 1, true), asset(2, 1, false), asset(3, 2, true)];
+        let their_inventory = vec![asset(4, 1, true)];
+        let problems = offer.validate(&our_inventory, &their_inventory);
+        
+        assert_eq!(problems, vec![
+            OfferProblem::MessageTooLong { length: MAX_MESSAGE_LENGTH + 1 },
+            OfferProblem::DuplicateAsset { side: OfferSide::Give, item: item(1, 1) },
+            OfferProblem::UntradableAsset { item: item(2, 1) },
+            OfferProblem::InvalidAmount { side: OfferSide::Give, item: item(3, 5), stack_size: 2 },
+            OfferProblem::MissingAsset { side: OfferSide::Receive, item: item(5, 1) },
+        ]);
+    }
+    
+    #[test]
+    fn validates_valid_offer() {
+        let offer = NewTradeOffer::builder(SteamID::from(76561198080179568))
+            .items_to_give(vec![item(1, 1)])
+            .build();
+        
+        assert!(offer.validate(&[asset(1, 1, true)], &[]).is_empty());
+    }
+}
@@ -1,6 +1,36 @@
 use serde::{Serialize, Deserialize};
+use crate::{
+    serializers::string,
+    types::{AppId, ContextId, CurrencyId, Amount},
+};
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+/// A wallet currency in a trade offer.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Currency {
+    pub appid: AppId,
+    #[serde(with = "string")]
+    pub contextid: ContextId,
+    #[serde(with = "string")]
+    pub currencyid: CurrencyId,
+    #[serde(with = "string")]
+    pub amount: Amount,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
     
-}
\ No newline at end of file
+    #[test]
+    fn serializes_numbers_as_strings() {
+        let currency = Currency {
+            appid: 753,
+            contextid: 4,
+            currencyid: 2001,
+            amount: 150,
+        };
+        let json = serde_json::to_string(&currency).unwrap();
+        
+        assert_eq!(json, r#"{"appid":753,"contextid":"4","currencyid":"2001","amount":"150"}"#);
+        assert_eq!(serde_json::from_str::<Currency>(&json).unwrap(), currency);
+    }
+}
@@ -19,9 +19,16 @@ use serde::{
 use serde_json::value::RawValue;
 use lazy_regex::{regex_is_match, regex_captures};
 use super::classinfo::ClassInfo;
-use crate::types::{
-    ClassInfoAppClass,
-    ClassInfoAppMap
+use crate::{
+    types::{
+        AppId,
+        ClassId,
+        InstanceId,
+        ClassInfoClass,
+        ClassInfoAppClass,
+        ClassInfoAppMap,
+    },
+    serializers::{string, option_string_0_as_none},
 };
 
 pub fn string_or_number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -206,7 +213,7 @@ where
                 0 => Ok(false),
                 1 => Ok(true),
                 other => Err(de::Error::invalid_value(
-                    Unexpected::Unsigned(other as u64),
+                    Unexpected::Unsigned(other),
                     &"zero or one",
                 )),
             }
@@ -409,10 +416,7 @@ where
             while let Some(key) = access.next_key::<String>()? {
                 if let Some((_, classid_string, instanceid_string)) = regex_captures!(r#"(\d+)_?(\d+)?"#, &key) {
                     let classid = classid_string.parse::<u64>().map_err(de::Error::custom)?;
-                    let instanceid = match instanceid_string.parse::<u64>() {
-                        Ok(instanceid) => Some(instanceid),
-                        Err(_) => None,
-                    };
+                    let instanceid = instanceid_string.parse::<u64>().ok();
                     let raw_value = access.next_value::<Box<RawValue>>()?;
                     let classinfo_string = raw_value.to_string();
                     
@@ -429,6 +433,52 @@ where
     deserializer.deserialize_any(ClassInfoMapVisitor)
 }
 
+/// Deserializes a sequence of classinfos which include their appid, such as the descriptions
+/// from GetTradeOffers, into their raw JSON strings.
+pub fn deserialize_classinfo_seq_raw<'de, D>(deserializer: D) -> Result<HashMap<ClassInfoClass, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Class {
+        appid: AppId,
+        #[serde(with = "string")]
+        classid: ClassId,
+        #[serde(default)]
+        #[serde(with = "option_string_0_as_none")]
+        instanceid: InstanceId,
+    }
+    
+    struct ClassInfoSeqVisitor;
+    
+    impl<'de> Visitor<'de> for ClassInfoSeqVisitor {
+        type Value = HashMap<ClassInfoClass, String>;
+        
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence of classinfos")
+        }
+        
+        fn visit_seq<M>(self, mut seq: M) -> Result<Self::Value, M::Error>
+        where
+            M: SeqAccess<'de>,
+        {
+            let mut map = HashMap::with_capacity(seq.size_hint().unwrap_or(0));
+            
+            while let Some(raw_value) = seq.next_element::<Box<RawValue>>()? {
+                let classinfo_string = raw_value.to_string();
+                let class = serde_json::from_str::<Class>(&classinfo_string)
+                    .map_err(de::Error::custom)?;
+                
+                map.insert((class.appid, class.classid, class.instanceid), classinfo_string);
+            }
+            
+            Ok(map)
+        }
+    }
+    
+    deserializer.deserialize_seq(ClassInfoSeqVisitor)
+}
+
 pub fn option_str_to_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
 where
     D: Deserializer<'de>,
@@ -6,12 +6,18 @@ pub mod user_details;
 pub mod accepted_offer;
 pub mod deserializers;
 pub mod currency;
+pub mod trade;
+pub mod trade_history;
+pub mod trade_offers_summary;
 
 pub use currency::Currency;
 pub use accepted_offer::AcceptedOffer;
-pub use user_details::UserDetails;
+pub use user_details::{UserDetails, TradeUserDetails, AppContextData, ContextData};
 pub use asset::Asset;
 pub use trade_offer::TradeOffer;
+pub use trade::{Trade, TradeAsset};
+pub use trade_history::TradeHistory;
+pub use trade_offers_summary::TradeOffersSummary;
 pub use sent_offer::SentOffer;
 pub use classinfo::{
     ClassInfo,
@@ -0,0 +1,73 @@
+use serde::{Serialize, Deserialize};
+use std::sync::Arc;
+use super::{asset::Asset, classinfo::ClassInfo};
+use crate::{
+    SteamID,
+    time::ServerTime,
+    enums::TradeStatus,
+    types::{
+        AppId,
+        ContextId,
+        AssetId,
+        Amount,
+        TradeId,
+        ClassInfoClass,
+    },
+};
+
+/// A completed or pending trade.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub tradeid: TradeId,
+    /// The other party in the trade.
+    pub partner: SteamID,
+    pub status: TradeStatus,
+    /// When the trade was initiated.
+    pub time_init: ServerTime,
+    /// When the trade leaves escrow, if the trade was held.
+    pub time_escrow_end: Option<ServerTime>,
+    pub assets_received: Vec<TradeAsset>,
+    pub assets_given: Vec<TradeAsset>,
+}
+
+impl Trade {
+    /// When the items in the trade were exchanged. This is when the trade left escrow for held 
+    /// trades, otherwise when the trade was initiated.
+    pub fn time_settled(&self) -> ServerTime {
+        self.time_escrow_end.unwrap_or(self.time_init)
+    }
+}
+
+/// An item exchanged in a trade.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TradeAsset {
+    pub appid: AppId,
+    /// The context ID the item was in before the trade.
+    pub contextid: ContextId,
+    /// The asset ID the item had before the trade.
+    pub assetid: AssetId,
+    pub amount: Amount,
+    /// The asset ID of the item after the trade. `None` if the items have not been exchanged.
+    pub new_assetid: Option<AssetId>,
+    /// The context ID of the item after the trade. `None` if the items have not been exchanged.
+    pub new_contextid: Option<ContextId>,
+    pub classinfo: Arc<ClassInfo>,
+}
+
+impl TradeAsset {
+    pub fn key(&self) -> ClassInfoClass {
+        (self.appid, self.classinfo.classid, self.classinfo.instanceid)
+    }
+    
+    /// Converts this into an [`Asset`] with the IDs the item has after the trade. Returns `None`
+    /// if the items have not been exchanged.
+    pub fn to_new_asset(&self) -> Option<Asset> {
+        Some(Asset {
+            appid: self.appid,
+            contextid: self.new_contextid?,
+            assetid: self.new_assetid?,
+            amount: self.amount,
+            classinfo: Arc::clone(&self.classinfo),
+        })
+    }
+}
@@ -0,0 +1,11 @@
+use super::trade::Trade;
+
+/// A page of trade history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeHistory {
+    pub trades: Vec<Trade>,
+    /// Whether there are more trades after this page.
+    pub more: bool,
+    /// The total number of trades.
+    pub total_trades: Option<u32>,
+}
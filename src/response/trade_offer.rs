@@ -8,7 +8,7 @@ use crate::{
     },
     types::{TradeId, TradeOfferId},
 };
-use super::asset::Asset;
+use super::{asset::Asset, currency::Currency};
 
 /// A trade offer.
 #[derive(Debug)]
@@ -19,6 +19,10 @@ pub struct TradeOffer {
     pub message: Option<String>,
     pub items_to_receive: Vec<Asset>,
     pub items_to_give: Vec<Asset>,
+    /// Wallet currency we receive.
+    pub currency_to_receive: Vec<Currency>,
+    /// Wallet currency we give.
+    pub currency_to_give: Vec<Currency>,
     pub is_our_offer: bool,
     pub from_real_time_trade: bool,
     pub expiration_time: ServerTime,
@@ -0,0 +1,67 @@
+use serde::Deserialize;
+
+/// Counts of offers from IEconService/GetTradeOffersSummary. "New" and "updated" counts are
+/// relative to the time given when getting the summary.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TradeOffersSummary {
+    #[serde(default)]
+    pub pending_received_count: u32,
+    #[serde(default)]
+    pub new_received_count: u32,
+    #[serde(default)]
+    pub updated_received_count: u32,
+    #[serde(default)]
+    pub historical_received_count: u32,
+    #[serde(default)]
+    pub pending_sent_count: u32,
+    #[serde(default)]
+    pub newly_accepted_sent_count: u32,
+    #[serde(default)]
+    pub updated_sent_count: u32,
+    #[serde(default)]
+    pub historical_sent_count: u32,
+    #[serde(default)]
+    pub escrow_received_count: u32,
+    #[serde(default)]
+    pub escrow_sent_count: u32,
+}
+
+impl TradeOffersSummary {
+    /// Checks if any offers are new or updated, or if any counts differ from a previous summary.
+    pub fn has_changes_since(&self, previous: &Self) -> bool {
+        self.new_received_count > 0 ||
+        self.updated_received_count > 0 ||
+        self.newly_accepted_sent_count > 0 ||
+        self.updated_sent_count > 0 ||
+        self.pending_received_count != previous.pending_received_count ||
+        self.historical_received_count != previous.historical_received_count ||
+        self.pending_sent_count != previous.pending_sent_count ||
+        self.historical_sent_count != previous.historical_sent_count ||
+        self.escrow_received_count != previous.escrow_received_count ||
+        self.escrow_sent_count != previous.escrow_sent_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn detects_changes() {
+        let previous = TradeOffersSummary {
+            pending_received_count: 1,
+            historical_sent_count: 10,
+            ..Default::default()
+        };
+        
+        assert!(!previous.clone().has_changes_since(&previous));
+        assert!(TradeOffersSummary {
+            new_received_count: 1,
+            ..previous.clone()
+        }.has_changes_since(&previous));
+        assert!(TradeOffersSummary {
+            historical_sent_count: 11,
+            ..previous.clone()
+        }.has_changes_since(&previous));
+    }
+}
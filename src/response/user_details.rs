@@ -1,10 +1,80 @@
 use std::cmp;
-use serde::Deserialize;
+use serde::{Serialize, Deserialize};
+use super::deserializers::hashmap_or_vec;
+use crate::{
+    serializers::string,
+    types::{AppId, ContextId},
+};
 
+/// Details for trading with a partner.
 #[derive(Deserialize, Debug)]
 pub struct UserDetails {
     pub them_escrow: u32,
     pub my_escrow: u32,
+    /// Our details from the trade offer page. `None` if the details were not loaded from the 
+    /// trade offer page.
+    #[serde(default)]
+    pub me: Option<TradeUserDetails>,
+    /// The partner's details from the trade offer page. `None` if the details were not loaded
+    /// from the trade offer page.
+    #[serde(default)]
+    pub them: Option<TradeUserDetails>,
+}
+
+/// Details about one side of a trade from the trade offer page.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TradeUserDetails {
+    pub persona_name: String,
+    /// The URL of the user's avatar.
+    pub avatar_url: Option<String>,
+    /// Whether the user's profile is private. `None` if this could not be determined.
+    pub profile_private: Option<bool>,
+    /// Whether the user's inventory is private. `None` if this could not be determined.
+    pub inventory_private: Option<bool>,
+    /// The apps the user has items in.
+    pub app_contexts: Vec<AppContextData>,
+}
+
+impl TradeUserDetails {
+    /// Gets the context data for an app.
+    pub fn get_app(&self, appid: AppId) -> Option<&AppContextData> {
+        self.app_contexts.iter().find(|app| app.appid == appid)
+    }
+}
+
+/// An app the user has items in, from `g_rgAppContextData`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AppContextData {
+    pub appid: AppId,
+    pub name: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// The number of items in all contexts.
+    #[serde(default)]
+    pub asset_count: u32,
+    #[serde(default)]
+    #[serde(rename = "rgContexts")]
+    #[serde(deserialize_with = "hashmap_or_vec")]
+    pub contexts: Vec<ContextData>,
+}
+
+impl AppContextData {
+    /// Gets the data for a context.
+    pub fn get_context(&self, contextid: ContextId) -> Option<&ContextData> {
+        self.contexts.iter().find(|context| context.contextid == contextid)
+    }
+}
+
+/// A context within an app.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ContextData {
+    #[serde(with = "string")]
+    #[serde(rename = "id")]
+    pub contextid: ContextId,
+    pub name: String,
+    /// The number of items in the context.
+    #[serde(default)]
+    pub asset_count: u32,
 }
 
 impl UserDetails {
@@ -26,9 +96,11 @@ mod tests {
         let details = UserDetails {
             them_escrow: 0,
             my_escrow: 3,
+            me: None,
+            them: None,
         };
 
-        assert_eq!(true, details.has_escrow());
+        assert!(details.has_escrow());
     }
     
     #[test]
@@ -36,6 +108,8 @@ mod tests {
         let details = UserDetails {
             them_escrow: 0,
             my_escrow: 3,
+            me: None,
+            them: None,
         };
 
         assert_eq!(3, details.hold_duration_days());
@@ -2,18 +2,15 @@ use std::time::{
     SystemTime,
     UNIX_EPOCH
 };
-use chrono::{NaiveDateTime, DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 
 pub type ServerTime = DateTime<Utc>;
 
 pub fn timestamp_to_server_time(timestamp: i64) -> ServerTime {
-    let naive_data_time = NaiveDateTime::from_timestamp(timestamp, 0);
-    let time: ServerTime = DateTime::from_utc(naive_data_time, Utc);
-
-    time
+    Utc.timestamp_opt(timestamp, 0).unwrap()
 }
 
-pub fn get_server_time_now() -> ServerTime {
+pub fn get_system_time_now() -> ServerTime {
     ServerTime::from(SystemTime::now())
 }
 
@@ -0,0 +1,120 @@
+use std::{fmt, str::FromStr};
+use steamid_ng::{SteamID, Instance, AccountType, Universe};
+use url::Url;
+use lazy_regex::regex_is_match;
+use crate::error::ParseTradeUrlError;
+
+const TRADE_URL_HOSTNAME: &str = "steamcommunity.com";
+const TRADE_URL_PATH: &str = "/tradeoffer/new/";
+
+/// A trade URL in the format `https://steamcommunity.com/tradeoffer/new/?partner=..&token=..`.
+/// The `partner` parameter is the partner's 32-bit account ID. The token is needed to send offers
+/// to partners we are not friends with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradeUrl {
+    pub partner: SteamID,
+    pub token: Option<String>,
+}
+
+impl TradeUrl {
+    pub fn new(partner: SteamID, token: Option<String>) -> Self {
+        Self {
+            partner,
+            token,
+        }
+    }
+    
+    /// Checks if a string is a valid trade offer access token.
+    pub fn is_valid_token(token: &str) -> bool {
+        regex_is_match!(r#"^[A-Za-z0-9_-]{8}$"#, token)
+    }
+}
+
+impl fmt::Display for TradeUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "https://{}{}?partner={}", TRADE_URL_HOSTNAME, TRADE_URL_PATH, self.partner.account_id())?;
+        
+        if let Some(token) = &self.token {
+            write!(f, "&token={}", token)?;
+        }
+        
+        Ok(())
+    }
+}
+
+impl FromStr for TradeUrl {
+    type Err = ParseTradeUrlError;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url = Url::parse(s.trim())?;
+        
+        if url.host_str() != Some(TRADE_URL_HOSTNAME) {
+            return Err(ParseTradeUrlError::InvalidHost);
+        }
+        
+        if url.path().trim_end_matches('/') != TRADE_URL_PATH.trim_end_matches('/') {
+            return Err(ParseTradeUrlError::InvalidPath);
+        }
+        
+        let mut partner = None;
+        let mut token = None;
+        
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "partner" => partner = Some(value.into_owned()),
+                "token" => token = Some(value.into_owned()),
+                _ => {},
+            }
+        }
+        
+        let accountid = partner
+            .ok_or(ParseTradeUrlError::MissingPartner)?
+            .parse::<u32>()
+            .map_err(|_| ParseTradeUrlError::InvalidPartner)?;
+        
+        if accountid == 0 {
+            return Err(ParseTradeUrlError::InvalidPartner);
+        }
+        
+        if let Some(token) = &token {
+            if !Self::is_valid_token(token) {
+                return Err(ParseTradeUrlError::InvalidToken);
+            }
+        }
+        
+        Ok(Self {
+            partner: SteamID::new(
+                accountid,
+                Instance::Desktop,
+                AccountType::Individual,
+                Universe::Public,
+            ),
+            token,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_trade_url() {
+        let trade_url = "https://steamcommunity.com/tradeoffer/new/?partner=119913840&token=Ab3_x-9Z"
+            .parse::<TradeUrl>()
+            .unwrap();
+        
+        assert_eq!(u64::from(trade_url.partner), 76561198080179568);
+        assert_eq!(trade_url.token.as_deref(), Some("Ab3_x-9Z"));
+        assert_eq!(trade_url.to_string(), "https://steamcommunity.com/tradeoffer/new/?partner=119913840&token=Ab3_x-9Z");
+    }
+    
+    #[test]
+    fn rejects_invalid_trade_urls() {
+        assert!(matches!("https://example.com/tradeoffer/new/?partner=119913840".parse::<TradeUrl>(), Err(ParseTradeUrlError::InvalidHost)));
+        assert!(matches!("https://steamcommunity.com/tradeoffer/new/?token=Ab3_x-9Z".parse::<TradeUrl>(), Err(ParseTradeUrlError::MissingPartner)));
+        assert!(matches!("https://steamcommunity.com/tradeoffer/new/?partner=abc".parse::<TradeUrl>(), Err(ParseTradeUrlError::InvalidPartner)));
+        assert!(matches!("https://steamcommunity.com/tradeoffer/new/?partner=119913840&token=bad".parse::<TradeUrl>(), Err(ParseTradeUrlError::InvalidToken)));
+        assert!("https://steamcommunity.com/tradeoffer/new?partner=119913840".parse::<TradeUrl>().is_ok());
+    }
+}
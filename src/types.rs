@@ -7,6 +7,7 @@ pub type Inventory = Vec<Asset>;
 pub type AppId = u32;
 pub type ContextId = u64;
 pub type AssetId = u64;
+pub type CurrencyId = u64;
 pub type Amount = u32;
 pub type ClassId = u64;
 pub type InstanceId = Option<u64>;